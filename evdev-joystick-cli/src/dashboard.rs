@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+
+use anyhow::Context;
+use evdev_joystick::{Joystick, JoystickState, axis_name};
+use evdev_rs::enums::EventCode;
+
+const BAR_WIDTH: usize = 40;
+
+/// Redraws the state of every axis, button and hat in place after each report, failing once
+/// the device is disconnected.
+pub fn dashboard(joystick: &Joystick) -> anyhow::Result<()> {
+    let mut state = joystick
+        .initial_state()
+        .context("Failed to read joystick state")?;
+    let mut out = io::stdout().lock();
+    write!(out, "\x1b[2J")?;
+    draw(&mut out, joystick, &state)?;
+    for frame in joystick.frames() {
+        let frame = frame.map_err(crate::read_error)?;
+        if frame.resync {
+            // Events were dropped, so start over from what the kernel now reports.
+            state = joystick
+                .initial_state()
+                .context("Failed to read joystick state")?;
+        }
+        for event in frame.events {
+            state.apply(&joystick.classify_event(event));
+        }
+        draw(&mut out, joystick, &state)?;
+    }
+    Ok(())
+}
+
+fn draw(out: &mut impl Write, joystick: &Joystick, state: &JoystickState) -> io::Result<()> {
+    // Overwrite in place rather than clearing, which flickers on every redraw.
+    write!(out, "\x1b[H")?;
+    writeln!(
+        out,
+        "{}\x1b[K",
+        joystick.name().as_deref().unwrap_or("(unnamed)")
+    )?;
+    writeln!(out, "\x1b[K")?;
+    for (axis, axis_state) in state.axes() {
+        let (minimum, maximum) = joystick
+            .abs_info(&EventCode::EV_ABS(axis))
+            .map_or((0, 0), |abs_info| (abs_info.minimum, abs_info.maximum));
+        writeln!(
+            out,
+            "{:<16} {minimum:>7} [{}] {maximum:<7} {:>7} ({:>6})\x1b[K",
+            axis_name(axis),
+            bar(axis_state.value, minimum, maximum),
+            axis_state.value,
+            axis_state.normalized,
+        )?;
+    }
+    writeln!(out, "\x1b[K")?;
+    for (index, pressed) in state.buttons() {
+        let name = joystick
+            .button_name(index)
+            .map_or_else(|| index.to_string(), str::to_owned);
+        if pressed {
+            // Reverse video, so held buttons stand out.
+            write!(out, "\x1b[7m{name}\x1b[0m ")?;
+        } else {
+            write!(out, "{name} ")?;
+        }
+    }
+    writeln!(out, "\x1b[K")?;
+    for hat in 0..4 {
+        if let Some(direction) = state.hat(hat) {
+            writeln!(out, "hat {hat}: {direction:?}\x1b[K")?;
+        }
+    }
+    write!(out, "\x1b[J")?;
+    out.flush()
+}
+
+/// A bar filled in proportion to where `value` lies between `minimum` and `maximum`.
+fn bar(value: i32, minimum: i32, maximum: i32) -> String {
+    let range = i64::from(maximum) - i64::from(minimum);
+    let filled = if range > 0 {
+        let offset = (i64::from(value) - i64::from(minimum)).clamp(0, range);
+        (offset * BAR_WIDTH as i64 / range) as usize
+    } else {
+        0
+    };
+    format!("{}{}", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))
+}
@@ -0,0 +1,103 @@
+use std::io::{self, Write};
+
+use evdev_joystick::{EventTime, Joystick, JoystickEvent, axis_name};
+use evdev_rs::{TimeVal, enums::EventCode};
+use serde::Serialize;
+
+/// One line of `monitor --format json` output, with `time` the event time as seconds with
+/// six digits of microseconds and `code` the raw event code.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum JsonEvent {
+    Abs {
+        time: String,
+        time_us: i64,
+        code: u32,
+        axis: &'static str,
+        value: i32,
+        normalized: i16,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<i32>,
+    },
+    Button {
+        time: String,
+        time_us: i64,
+        code: Option<u32>,
+        name: Option<&'static str>,
+        index: u32,
+        value: i32,
+        pressed: bool,
+    },
+    Hat {
+        time: String,
+        time_us: i64,
+        hat: u8,
+        direction: String,
+    },
+}
+
+/// Streams events as newline-delimited JSON objects, failing once the device is disconnected.
+pub fn monitor_json(joystick: &Joystick) -> anyhow::Result<()> {
+    let mut out = io::stdout().lock();
+    for event in joystick.events() {
+        let event = event.map_err(crate::read_error)?;
+        // Kept for buttons, whose typed event only says whether they are pressed, e.g. to
+        // tell autorepeat (2) from a press (1).
+        let raw_value = event.value;
+        let json = match joystick.classify_event(event) {
+            JoystickEvent::AxisMoved {
+                time,
+                axis,
+                value,
+                normalized,
+            } => {
+                let abs_info = joystick.abs_info(&EventCode::EV_ABS(axis));
+                JsonEvent::Abs {
+                    time: EventTime(time).to_string(),
+                    time_us: micros(time),
+                    code: axis as u32,
+                    axis: axis_name(axis),
+                    value,
+                    normalized,
+                    min: abs_info.map(|abs_info| abs_info.minimum),
+                    max: abs_info.map(|abs_info| abs_info.maximum),
+                }
+            }
+            JoystickEvent::ButtonChanged {
+                time,
+                index,
+                pressed,
+            } => JsonEvent::Button {
+                time: EventTime(time).to_string(),
+                time_us: micros(time),
+                code: joystick.button_code(index),
+                name: joystick.button_name(index),
+                index,
+                value: raw_value,
+                pressed,
+            },
+            JoystickEvent::HatMoved {
+                time,
+                hat,
+                direction,
+            } => JsonEvent::Hat {
+                time: EventTime(time).to_string(),
+                time_us: micros(time),
+                hat,
+                direction: format!("{direction:?}"),
+            },
+            JoystickEvent::Sync { .. } | JoystickEvent::Other(_) => continue,
+        };
+        serde_json::to_writer(&mut out, &json)?;
+        // Flush per line so consumers like jq see events as they happen.
+        writeln!(out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn micros(time: TimeVal) -> i64 {
+    time.tv_sec * 1_000_000 + time.tv_usec
+}
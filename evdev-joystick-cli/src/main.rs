@@ -1,48 +1,275 @@
-use std::path::PathBuf;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::{Parser, Subcommand, ValueEnum};
+use evdev_joystick::{
+    EventTime, Joystick, JoystickError, JoystickEvent, axis_from_name, axis_name,
+};
+use evdev_rs::enums::{EV_ABS, EventCode, int_to_ev_abs};
+
+mod dashboard;
+mod json;
 
-use clap::Parser;
-use evdev_joystick::Joystick;
-use evdev_rs::{InputEvent, enums::EventType};
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    #[arg(short, long)]
-    device: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List every connected joystick
+    List,
+    /// Print everything a device reports about itself
+    Info {
+        #[arg(short, long)]
+        device: PathBuf,
+        /// Print the capabilities as JSON, e.g. to attach to a bug report
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stream a device's events
+    Monitor {
+        #[arg(short, long)]
+        device: PathBuf,
+        /// Redraw a live view of every axis and button instead of logging events
+        #[arg(long, conflicts_with = "format")]
+        dashboard: bool,
+        /// How to print events
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Write new calibration for an axis to the device
+    Set {
+        #[arg(short, long)]
+        device: PathBuf,
+        /// The axis, by name (e.g. ABS_RY) or code
+        #[arg(short, long, value_parser = parse_axis)]
+        axis: EV_ABS,
+        /// Deadzone around the centre, in raw units
+        #[arg(long)]
+        flat: Option<i32>,
+        /// New minimum of the reported range
+        #[arg(long, allow_negative_numbers = true)]
+        min: Option<i32>,
+        /// New maximum of the reported range
+        #[arg(long, allow_negative_numbers = true)]
+        max: Option<i32>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// One human-readable line per event
+    Text,
+    /// One JSON object per line, for scripting
+    Json,
+}
+
+fn parse_axis(axis: &str) -> Result<EV_ABS, String> {
+    let parsed = match axis.parse::<u32>() {
+        Ok(code) => int_to_ev_abs(code),
+        Err(_) => axis_from_name(axis),
+    };
+    parsed.ok_or_else(|| format!("{axis} is not an absolute axis"))
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-    let joystick = Joystick::new_from_path(args.device)?;
-    for event in joystick.events() {
-        let InputEvent {
-            time,
-            event_code,
-            value,
-        } = event;
-        match event.event_type() {
-            Some(EventType::EV_ABS) => {
-                let abs_info = joystick
-                    .abs_info(&event_code)
-                    .expect("Joystick axis must be enabled");
-                println!(
-                    "{}.{}: code {}, {}",
-                    time.tv_sec, time.tv_usec, event_code, abs_info
-                );
+    match Args::parse().command {
+        Command::List => list(),
+        Command::Info { device, json } => info(&Joystick::new_from_path(device)?, json),
+        Command::Monitor {
+            device,
+            dashboard,
+            format,
+        } => {
+            let joystick = Joystick::new_from_path(device)?;
+            match format {
+                _ if dashboard => dashboard::dashboard(&joystick),
+                Format::Text => monitor(&joystick),
+                Format::Json => json::monitor_json(&joystick),
+            }
+        }
+        Command::Set {
+            device,
+            axis,
+            flat,
+            min,
+            max,
+        } => set(&Joystick::new_from_path(device)?, axis, flat, min, max),
+    }
+}
+
+fn set(
+    joystick: &Joystick,
+    axis: EV_ABS,
+    flat: Option<i32>,
+    min: Option<i32>,
+    max: Option<i32>,
+) -> anyhow::Result<()> {
+    if flat.is_none() && min.is_none() && max.is_none() {
+        bail!("Nothing to set, pass at least one of --flat, --min or --max");
+    }
+    let name = axis_name(axis);
+    let mut abs_info = joystick
+        .abs_info(&EventCode::EV_ABS(axis))
+        .with_context(|| format!("Joystick has no axis {name}"))?;
+    println!("Before: {name}: {abs_info}");
+    if let Some(flat) = flat {
+        abs_info.set_flat(flat);
+    }
+    let (minimum, maximum) = (abs_info.minimum, abs_info.maximum);
+    abs_info.set_range(min.unwrap_or(minimum), max.unwrap_or(maximum));
+    joystick
+        .write_abs_info(axis, &abs_info)
+        .with_context(|| format!("Failed to write calibration of {name}"))?;
+    let abs_info = joystick
+        .abs_info(&EventCode::EV_ABS(axis))
+        .with_context(|| format!("Joystick has no axis {name}"))?;
+    println!("After:  {name}: {abs_info}");
+    Ok(())
+}
+
+fn info(joystick: &Joystick, json: bool) -> anyhow::Result<()> {
+    let capabilities = joystick.capabilities();
+    if json {
+        serde_json::to_writer_pretty(io::stdout().lock(), &capabilities)?;
+        println!();
+        return Ok(());
+    }
+    // Name, ids and every axis with its current info.
+    println!("{joystick}");
+    println!(
+        "Driver version: {}",
+        version_string(capabilities.driver_version)
+    );
+    for button in &capabilities.buttons {
+        match &button.name {
+            Some(name) => println!("Button {}: {}", button.index, name),
+            None => println!("Button {}: key {}", button.index, button.code),
+        }
+    }
+    if !capabilities.rel_axes.is_empty() {
+        println!("Relative axes: {}", capabilities.rel_axes.join(", "));
+    }
+    if !capabilities.leds.is_empty() {
+        println!("LEDs: {}", capabilities.leds.join(", "));
+    }
+    Ok(())
+}
+
+/// A driver version as `major.minor.patch`.
+fn version_string(version: i32) -> String {
+    format!(
+        "{}.{}.{}",
+        version >> 16,
+        (version >> 8) & 0xff,
+        version & 0xff
+    )
+}
+
+fn list() -> anyhow::Result<()> {
+    let joysticks = match Joystick::joysticks() {
+        Ok(joysticks) => joysticks,
+        // Neither /dev/input/by-id nor /dev/input exist, e.g. in a container.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            println!("No input devices found, /dev/input is missing");
+            return Ok(());
+        }
+        Err(e) => return Err(e).context("Failed to list joysticks"),
+    };
+    let mut found = false;
+    for joystick in joysticks {
+        found = true;
+        match joystick {
+            Ok(joystick) => println!(
+                "{}: {} ({} axes, {} buttons)",
+                joystick.path().unwrap_or(Path::new("?")).display(),
+                joystick.name().as_deref().unwrap_or("(unnamed)"),
+                joystick.num_abs_axes(),
+                joystick.num_buttons()
+            ),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                println!("{e} (try adding yourself to the input group)")
             }
-            Some(EventType::EV_KEY) => {
+            Err(e) => println!("{e}"),
+        }
+    }
+    if !found {
+        println!("No joysticks found");
+    }
+    Ok(())
+}
+
+/// The error to exit with when reading events fails. An unplugged device gets a message of
+/// its own, so it still exits nonzero but reads as more than an I/O error.
+fn read_error(error: io::Error) -> anyhow::Error {
+    match JoystickError::from(error) {
+        JoystickError::Disconnected(_) => anyhow!("Joystick disconnected"),
+        e => anyhow::Error::new(e).context("Failed to read joystick event"),
+    }
+}
+
+fn monitor(joystick: &Joystick) -> anyhow::Result<()> {
+    println!(
+        "Device: {}",
+        joystick.name().as_deref().unwrap_or("(unnamed)")
+    );
+    let version = joystick
+        .driver_version()
+        .context("Failed to read driver version")?;
+    println!("Driver version: {}", version_string(version));
+    for event in joystick.typed_events() {
+        let event = event.map_err(read_error)?;
+        match event {
+            JoystickEvent::AxisMoved {
+                time,
+                axis,
+                value,
+                normalized,
+            } => {
+                let name = axis_name(axis);
+                match joystick.abs_info(&EventCode::EV_ABS(axis)) {
+                    Some(abs_info) => println!("{}: code {}, {}", EventTime(time), name, abs_info),
+                    None => println!(
+                        "{}: code {}, (value: {} (norm: {}))",
+                        EventTime(time),
+                        name,
+                        value,
+                        normalized
+                    ),
+                }
+            }
+            JoystickEvent::ButtonChanged {
+                time,
+                index,
+                pressed,
+            } => {
+                // Codes libevdev has no name for are shown as the raw key code instead.
+                let name = match joystick.button_name(index) {
+                    Some(name) => name.to_owned(),
+                    None => format!("key {}", joystick.button_code(index).unwrap_or_default()),
+                };
                 println!(
-                    "{}.{}: code BTN_{:?}, {}",
-                    time.tv_sec,
-                    time.tv_usec,
-                    joystick
-                        .get_button_index(&event_code)
-                        .expect("Button pressed must be enabled")
-                        + 1,
-                    value
+                    "{}: {} (button {}): {}",
+                    EventTime(time),
+                    name,
+                    index,
+                    if pressed { "pressed" } else { "released" }
                 );
             }
-            Some(_) => (),
-            None => (),
+            JoystickEvent::HatMoved {
+                time,
+                hat,
+                direction,
+            } => {
+                println!("{}: hat {}, {:?}", EventTime(time), hat, direction);
+            }
+            JoystickEvent::Sync { .. } | JoystickEvent::Other(_) => (),
         }
     }
     Ok(())
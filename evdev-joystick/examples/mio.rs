@@ -0,0 +1,53 @@
+//! Waits on two joysticks and a TCP listener in a single `mio::Poll`.
+//!
+//! Usage: `cargo run --example mio --features mio -- <device> <device>`
+
+use std::{env, io};
+
+use evdev_joystick::Joystick;
+use mio::{Events, Interest, Poll, Token, net::TcpListener};
+
+const LISTENER: Token = Token(2);
+
+fn main() -> io::Result<()> {
+    let mut joysticks = env::args()
+        .skip(1)
+        .take(2)
+        .map(Joystick::new_from_path)
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut listener = TcpListener::bind("127.0.0.1:0".parse().expect("Valid address"))?;
+    println!("Listening on {}", listener.local_addr()?);
+
+    let mut poll = Poll::new()?;
+    for (i, joystick) in joysticks.iter_mut().enumerate() {
+        poll.registry()
+            .register(joystick, Token(i), Interest::READABLE)?;
+    }
+    poll.registry()
+        .register(&mut listener, LISTENER, Interest::READABLE)?;
+
+    let mut events = Events::with_capacity(16);
+    loop {
+        poll.poll(&mut events, None)?;
+        for event in &events {
+            match event.token() {
+                LISTENER => {
+                    // Edge-triggered, so accept until it would block.
+                    loop {
+                        match listener.accept() {
+                            Ok((_, addr)) => println!("Connection from {addr}"),
+                            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+                Token(i) => {
+                    for event in joysticks[i].drain_events() {
+                        let event = event?;
+                        println!("Joystick {i}: {:?} {}", event.event_code, event.value);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    path::Path,
+};
+
+use evdev_rs::{
+    AbsInfo, DeviceWrapper,
+    enums::{EV_ABS, EventCode, EventType, int_to_ev_abs},
+};
+use libc::c_ulong;
+
+use super::{Joystick, JoystickAbsInfo, axis_name};
+use crate::raw;
+
+impl JoystickAbsInfo {
+    pub fn set_flat(&mut self, flat: i32) -> &mut Self {
+        self.flat = flat;
+        self
+    }
+
+    pub fn set_fuzz(&mut self, fuzz: i32) -> &mut Self {
+        self.fuzz = fuzz;
+        self
+    }
+
+    pub fn set_resolution(&mut self, resolution: i32) -> &mut Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn set_range(&mut self, minimum: i32, maximum: i32) -> &mut Self {
+        self.minimum = minimum;
+        self.maximum = maximum;
+        self
+    }
+
+    /// Opens the device at `path` read-only just to read the info of `axis`, for one-off
+    /// reads without a [`Joystick`]. Fails like [`AbsCapabilities::get`].
+    pub fn read_from_path(path: impl AsRef<Path>, axis: EV_ABS) -> io::Result<Self> {
+        let file = File::open(path)?;
+        AbsCapabilities::from_file(&file)?.get(axis)
+    }
+
+    /// Opens the device at `path` read-write just to write this as the info of `axis`. Fails
+    /// like [`AbsCapabilities::set`].
+    pub fn write_to_path(&self, path: impl AsRef<Path>, axis: EV_ABS) -> io::Result<()> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        AbsCapabilities::from_file(&file)?.set(axis, self)
+    }
+}
+
+impl Joystick {
+    /// The current state of every absolute axis. Values are those libevdev tracks: read from
+    /// the kernel when the device was opened, then updated by every event read since.
+    pub fn axis_snapshot(&self) -> BTreeMap<EV_ABS, JoystickAbsInfo> {
+        self.all_abs_info().into_iter().collect()
+    }
+
+    /// Like [`Joystick::axis_snapshot`], keeping only the normalized values.
+    pub fn axis_values(&self) -> BTreeMap<EV_ABS, i16> {
+        self.all_abs_info()
+            .into_iter()
+            .map(|(axis, abs_info)| (axis, abs_info.normalized_value()))
+            .collect()
+    }
+
+    /// The info of every axis in [`Joystick::abs_axis`], in the same order. The axis list is
+    /// scanned once when the device is opened, so this doesn't query the capability bits
+    /// again. Axes without info are skipped.
+    pub fn all_abs_info(&self) -> Vec<(EV_ABS, JoystickAbsInfo)> {
+        self.abs_axis()
+            .filter_map(|axis| Some((axis, self.abs_info_for(axis)?)))
+            .collect()
+    }
+
+    /// Reads the info of every axis from the kernel rather than libevdev's cached copy,
+    /// sorted by axis. The supported axes are read once with `EVIOCGBIT`, then `EVIOCGABS` is
+    /// only issued for the axes that are present.
+    pub fn read_all_abs_info(&self) -> io::Result<Vec<(EV_ABS, JoystickAbsInfo)>> {
+        let capabilities = self.abs_capabilities()?;
+        capabilities
+            .present_axes()
+            .into_iter()
+            .filter_map(|code| int_to_ev_abs(code.into()))
+            .map(|axis| Ok((axis, capabilities.get(axis)?)))
+            .collect()
+    }
+
+    /// Reads the supported axes once, for reading or writing the info of several axes
+    /// without querying them again each time.
+    pub fn abs_capabilities(&self) -> io::Result<AbsCapabilities<'_>> {
+        AbsCapabilities::from_file(self.device.file())
+    }
+
+    /// Writes the range, fuzz, flat and resolution of `axis` to the kernel with `EVIOCSABS`,
+    /// so they apply to every reader of the device until it is unplugged.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] unless `minimum` is less than `maximum`, since
+    /// an empty range can't be normalized.
+    pub fn write_abs_info(&self, axis: EV_ABS, abs_info: &JoystickAbsInfo) -> io::Result<()> {
+        set_abs_info(self.fd(), axis, abs_info)?;
+        // Keep libevdev's cached copy in sync with what the kernel now reports.
+        self.device.set_abs_info(&EventCode::EV_ABS(axis), abs_info);
+        Ok(())
+    }
+
+    /// Sets the deadzone (`flat`) of `axis` in raw units, keeping the rest of its info. Like
+    /// [`Joystick::write_abs_info`] this applies to every reader of the device.
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if the device has no such axis and with
+    /// [`io::ErrorKind::InvalidInput`] if `flat` is negative.
+    pub fn set_deadzone(&self, axis: EV_ABS, flat: i32) -> io::Result<()> {
+        self.update_abs_info(axis, "flat", flat, |abs_info| abs_info.set_flat(flat))
+    }
+
+    /// Sets the fuzz (the noise the kernel filters out) of `axis` in raw units, like
+    /// [`Joystick::set_deadzone`].
+    pub fn set_fuzz(&self, axis: EV_ABS, fuzz: i32) -> io::Result<()> {
+        self.update_abs_info(axis, "fuzz", fuzz, |abs_info| abs_info.set_fuzz(fuzz))
+    }
+
+    fn update_abs_info(
+        &self,
+        axis: EV_ABS,
+        field: &str,
+        value: i32,
+        update: impl FnOnce(&mut JoystickAbsInfo) -> &mut JoystickAbsInfo,
+    ) -> io::Result<()> {
+        if value < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("axis {field} must not be negative, got {value}"),
+            ));
+        }
+        let mut abs_info = self.abs_info_for(axis).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("joystick has no axis {}", axis_name(axis)),
+            )
+        })?;
+        update(&mut abs_info);
+        self.write_abs_info(axis, &abs_info)
+    }
+}
+
+/// The axes a device supports, read once with `EVIOCGBIT` so that reading and writing axis
+/// info with `EVIOCGABS` and `EVIOCSABS` doesn't need to check them again. Created with
+/// [`Joystick::abs_capabilities`] or from any evdev file.
+///
+/// Unlike the [`Joystick`] methods, this goes straight to the kernel: [`AbsCapabilities::set`]
+/// doesn't update the info libevdev caches for [`Joystick::abs_info`].
+#[derive(Debug, Clone)]
+pub struct AbsCapabilities<'a> {
+    fd: BorrowedFd<'a>,
+    bits: Vec<c_ulong>,
+}
+
+impl<'a> AbsCapabilities<'a> {
+    pub fn from_file(file: &'a File) -> io::Result<Self> {
+        Self::from_fd(file.as_fd())
+    }
+
+    pub fn from_fd(fd: BorrowedFd<'a>) -> io::Result<Self> {
+        let mut bits = raw::bitmask(EV_ABS::ABS_MAX as usize);
+        // SAFETY: the buffer's length is passed along with it.
+        unsafe { raw::evioc_get_bit(fd.as_raw_fd(), EventType::EV_ABS as u32, &mut bits) }?;
+        Ok(AbsCapabilities { fd, bits })
+    }
+
+    pub fn has(&self, axis: EV_ABS) -> bool {
+        raw::test_bit(&self.bits, axis as usize)
+    }
+
+    /// The codes of every axis the device supports, up to and including `ABS_MAX`, in
+    /// ascending order. Includes codes evdev-rs has no [`EV_ABS`] variant for.
+    pub fn present_axes(&self) -> Vec<u16> {
+        (0..=EV_ABS::ABS_MAX as u16)
+            .filter(|&code| raw::test_bit(&self.bits, code.into()))
+            .collect()
+    }
+
+    /// Reads the info of `axis` with `EVIOCGABS`. Fails with [`io::ErrorKind::NotFound`],
+    /// without asking the kernel, if the device has no such axis.
+    pub fn get(&self, axis: EV_ABS) -> io::Result<JoystickAbsInfo> {
+        self.check(axis)?;
+        // SAFETY: input_absinfo is plain integers, for which all zeroes is valid.
+        let mut raw_info: libc::input_absinfo = unsafe { std::mem::zeroed() };
+        // SAFETY: `raw_info` is a writable input_absinfo for the duration of the call.
+        unsafe { raw::evioc_get_abs(self.fd.as_raw_fd(), axis as u32, &mut raw_info) }?;
+        Ok(JoystickAbsInfo(AbsInfo::from_raw(raw_info)))
+    }
+
+    /// Writes the info of `axis` with `EVIOCSABS`, failing like [`AbsCapabilities::get`] if
+    /// there is no such axis and like [`Joystick::write_abs_info`] for an inverted range.
+    pub fn set(&self, axis: EV_ABS, abs_info: &JoystickAbsInfo) -> io::Result<()> {
+        self.check(axis)?;
+        set_abs_info(self.fd.as_raw_fd(), axis, abs_info)
+    }
+
+    fn check(&self, axis: EV_ABS) -> io::Result<()> {
+        if self.has(axis) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("device has no axis {}", axis_name(axis)),
+            ))
+        }
+    }
+}
+
+fn set_abs_info(fd: RawFd, axis: EV_ABS, abs_info: &JoystickAbsInfo) -> io::Result<()> {
+    if abs_info.minimum >= abs_info.maximum {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "axis minimum {} is not less than its maximum {}",
+                abs_info.minimum, abs_info.maximum
+            ),
+        ));
+    }
+    let raw_info = abs_info.as_raw();
+    // SAFETY: `raw_info` is a valid input_absinfo for the duration of the call.
+    unsafe { raw::evioc_set_abs(fd, axis as u32, &raw_info) }?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abs_capabilities_cached() {
+        let file = File::open("/dev/null").expect("/dev/null can be opened");
+        let err = AbsCapabilities::from_file(&file).expect_err("/dev/null has no axes to read");
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTTY));
+
+        // Stand in for a device with only ABS_Y, so any ioctl issued fails with ENOTTY.
+        let mut bits = raw::bitmask(EV_ABS::ABS_MAX as usize);
+        bits[0] = 1 << EV_ABS::ABS_Y as usize;
+        let capabilities = AbsCapabilities {
+            fd: file.as_fd(),
+            bits,
+        };
+        assert!(capabilities.has(EV_ABS::ABS_Y));
+        let present = capabilities.present_axes();
+        assert_eq!(present, [EV_ABS::ABS_Y as u16]);
+        for code in present {
+            let axis = int_to_ev_abs(code.into()).expect("Present axis is an EV_ABS");
+            assert!(capabilities.has(axis));
+        }
+        // Lookups of absent axes are all answered from the bitmask, without an ioctl.
+        for axis in (0..10)
+            .filter_map(int_to_ev_abs)
+            .filter(|&a| a != EV_ABS::ABS_Y)
+        {
+            let err = capabilities.get(axis).expect_err("Axis is absent");
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+        let abs_info = JoystickAbsInfo(AbsInfo {
+            value: 0,
+            minimum: 0,
+            maximum: 1,
+            fuzz: 0,
+            flat: 0,
+            resolution: 0,
+        });
+        let err = capabilities
+            .set(EV_ABS::ABS_X, &abs_info)
+            .expect_err("Axis is absent");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        // The range is checked before any ioctl, so this fails the same without a device.
+        let empty = JoystickAbsInfo(AbsInfo {
+            minimum: 1,
+            ..*abs_info
+        });
+        let err = capabilities
+            .set(EV_ABS::ABS_Y, &empty)
+            .expect_err("Empty range is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = capabilities
+            .get(EV_ABS::ABS_Y)
+            .expect_err("Present axis is read from the kernel");
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTTY));
+    }
+
+    #[test]
+    fn test_abs_info_round_trip() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let devnode = device
+            .devnode()
+            .expect("Virtual joystick has a device node");
+        let mut abs_info = joystick
+            .abs_info(&EventCode::EV_ABS(EV_ABS::ABS_RZ))
+            .expect("Virtual joystick has ABS_RZ");
+        assert_eq!((abs_info.minimum, abs_info.maximum), (0, 255));
+        joystick
+            .write_abs_info(EV_ABS::ABS_RZ, abs_info.set_flat(8).set_fuzz(2))
+            .expect("Axis info can be written");
+        let read = JoystickAbsInfo::read_from_path(devnode, EV_ABS::ABS_RZ)
+            .expect("Axis info can be read back");
+        assert_eq!((read.flat, read.fuzz), (8, 2));
+        let all = joystick
+            .read_all_abs_info()
+            .expect("Axis info can be read from the kernel");
+        let axes = all.iter().map(|&(axis, _)| axis).collect::<Vec<_>>();
+        assert_eq!(axes, [EV_ABS::ABS_X, EV_ABS::ABS_Y, EV_ABS::ABS_RZ]);
+    }
+
+    #[test]
+    fn test_read_from_path() {
+        let err = JoystickAbsInfo::read_from_path("/nonexistent/evdev-joystick", EV_ABS::ABS_X)
+            .expect_err("Missing device can't be read");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        let err = JoystickAbsInfo::read_from_path("/dev/null", EV_ABS::ABS_X)
+            .expect_err("/dev/null has no axes");
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTTY));
+    }
+
+    #[test]
+    fn test_abs_info_eq() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let devnode = device
+            .devnode()
+            .expect("Virtual joystick has a device node");
+        let first =
+            JoystickAbsInfo::read_from_path(devnode, EV_ABS::ABS_X).expect("Axis info can be read");
+        let second = JoystickAbsInfo::read_from_path(devnode, EV_ABS::ABS_X)
+            .expect("Axis info can be read again");
+        assert_eq!(first, second);
+        let cached = joystick
+            .abs_info(&EventCode::EV_ABS(EV_ABS::ABS_X))
+            .expect("Virtual joystick has ABS_X");
+        assert_eq!(first, cached);
+    }
+
+    #[test]
+    fn test_abs_info_traits() {
+        use std::collections::HashSet;
+
+        let abs_info = JoystickAbsInfo(AbsInfo {
+            value: 0,
+            minimum: -512,
+            maximum: 511,
+            fuzz: 0,
+            flat: 16,
+            resolution: 0,
+        });
+        let mut changed = abs_info;
+        assert_eq!(abs_info, changed);
+        changed.set_fuzz(4);
+        assert_ne!(abs_info, changed);
+        let mut moved = abs_info;
+        moved.value = 1;
+        assert_ne!(abs_info, moved);
+        let set = HashSet::from([abs_info, abs_info, changed, moved]);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut abs_info = JoystickAbsInfo(AbsInfo {
+            value: 0,
+            minimum: 0,
+            maximum: 255,
+            fuzz: 0,
+            flat: 0,
+            resolution: 0,
+        });
+        abs_info
+            .set_range(-512, 511)
+            .set_flat(16)
+            .set_fuzz(4)
+            .set_resolution(2);
+        assert_eq!(
+            (
+                abs_info.minimum,
+                abs_info.maximum,
+                abs_info.flat,
+                abs_info.fuzz,
+                abs_info.resolution
+            ),
+            (-512, 511, 16, 4, 2)
+        );
+    }
+
+    #[test]
+    fn test_write_abs_info() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let axis = joystick
+            .abs_axis()
+            .next()
+            .expect("Joystick must have at least one absolute axis");
+        let mut abs_info = joystick
+            .abs_info(&EventCode::EV_ABS(axis))
+            .expect("Axis is valid");
+        let original = *abs_info;
+        let err = joystick
+            .write_abs_info(axis, abs_info.set_range(1, 0))
+            .expect_err("Inverted range is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        abs_info.0 = original;
+        joystick
+            .write_abs_info(axis, abs_info.set_resolution(original.resolution + 1))
+            .expect("Resolution can be written");
+        joystick
+            .write_abs_info(axis, &JoystickAbsInfo(original))
+            .expect("Original calibration can be restored");
+    }
+
+    #[test]
+    fn test_read_all_abs_info() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let read = joystick
+            .read_all_abs_info()
+            .expect("Axis info can be read from the kernel");
+        let axes = read.iter().map(|&(axis, _)| axis).collect::<Vec<_>>();
+        assert_eq!(axes, joystick.abs_axis().collect::<Vec<_>>());
+        for (axis, abs_info) in &read {
+            let cached = joystick
+                .abs_info(&EventCode::EV_ABS(*axis))
+                .expect("Axis is valid");
+            assert_eq!(abs_info.maximum, cached.maximum);
+        }
+    }
+
+    #[test]
+    fn test_set_deadzone() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let axis = joystick
+            .abs_axis()
+            .next()
+            .expect("Joystick must have at least one absolute axis");
+        let code = EventCode::EV_ABS(axis);
+        let original = *joystick.abs_info(&code).expect("Axis is valid");
+        joystick.set_deadzone(axis, 7).expect("Deadzone can be set");
+        joystick.set_fuzz(axis, 3).expect("Fuzz can be set");
+        let abs_info = joystick.abs_info(&code).expect("Axis is valid");
+        assert_eq!((abs_info.flat, abs_info.fuzz), (7, 3));
+        assert_eq!(abs_info.maximum, original.maximum);
+        let err = joystick
+            .set_deadzone(axis, -1)
+            .expect_err("Negative flat is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = joystick
+            .set_fuzz(EV_ABS::ABS_MT_TOOL_Y, 0)
+            .expect_err("Missing axis is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        joystick
+            .write_abs_info(axis, &JoystickAbsInfo(original))
+            .expect("Original calibration can be restored");
+    }
+}
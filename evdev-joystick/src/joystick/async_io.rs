@@ -0,0 +1,84 @@
+use std::{
+    io,
+    os::fd::{AsFd, BorrowedFd},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_io::Async;
+use evdev_rs::{Device, InputEvent};
+use futures_core::Stream;
+
+use super::{Joystick, events::poll_next_event};
+
+/// Runtime-agnostic stream of a joystick's events, driven by `async-io`'s reactor.
+///
+/// Works with smol, async-std or any executor; with tokio prefer [`Joystick::event_stream`]
+/// from the `tokio` feature, which registers with tokio's own reactor instead of spawning
+/// `async-io`'s. The stream ends after yielding an error, like [`Joystick::events`].
+pub struct AsyncIoEventStream<'a> {
+    device: &'a Device,
+    fd: Async<BorrowedFd<'a>>,
+    done: bool,
+}
+
+impl Joystick {
+    /// Streams events as they arrive, resyncing after a `SYN_DROPPED` the same way as
+    /// [`Joystick::events`] does.
+    pub fn async_io_event_stream(&self) -> io::Result<AsyncIoEventStream<'_>> {
+        Ok(AsyncIoEventStream {
+            device: &self.device,
+            fd: Async::new(self.device.file().as_fd())?,
+            done: false,
+        })
+    }
+}
+
+impl Stream for AsyncIoEventStream<'_> {
+    type Item = io::Result<InputEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let AsyncIoEventStream { device, fd, done } = self.get_mut();
+        poll_next_event(device, done, || fd.poll_readable(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, pin::pin};
+
+    use evdev_rs::{
+        TimeVal,
+        enums::{EV_KEY, EventCode},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_async_io_event_stream() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        smol::block_on(async {
+            let mut stream = pin!(
+                joystick
+                    .async_io_event_stream()
+                    .expect("Device can be registered")
+            );
+            device
+                .emit(&[InputEvent::new(
+                    &TimeVal::new(0, 0),
+                    &EventCode::EV_KEY(EV_KEY::BTN_THUMB),
+                    1,
+                )])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+            let event = poll_fn(|cx| stream.as_mut().poll_next(cx))
+                .await
+                .expect("Stream yields an event")
+                .expect("Event is read without error");
+            assert_eq!(event.event_code, EventCode::EV_KEY(EV_KEY::BTN_THUMB));
+            assert_eq!(event.value, 1);
+        });
+    }
+}
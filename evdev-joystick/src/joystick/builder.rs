@@ -0,0 +1,114 @@
+use std::{fs::OpenOptions, io, os::unix::fs::OpenOptionsExt, path::Path};
+
+use evdev_rs::Device;
+
+use super::Joystick;
+
+/// Options for opening a [`Joystick`], created with [`Joystick::builder`]. The defaults match
+/// [`Joystick::new_from_path`]: non-blocking, read-write and not grabbed.
+#[derive(Debug, Clone)]
+pub struct JoystickBuilder {
+    nonblocking: bool,
+    grab: bool,
+    read_write: bool,
+}
+
+impl Joystick {
+    pub fn builder() -> JoystickBuilder {
+        JoystickBuilder {
+            nonblocking: true,
+            grab: false,
+            read_write: true,
+        }
+    }
+}
+
+impl JoystickBuilder {
+    /// Opens the device with `O_NONBLOCK`. Without it, [`Joystick::events_nonblocking`] and
+    /// [`Joystick::next_event_timeout`] wait for an event like [`Joystick::events`] does.
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+
+    /// Takes an exclusive grab with `EVIOCGRAB` right after opening, see [`Joystick::grab`].
+    pub fn grab(mut self, grab: bool) -> Self {
+        self.grab = grab;
+        self
+    }
+
+    /// Opens the device with `O_RDWR` rather than `O_RDONLY`, which force feedback, LEDs and
+    /// writing calibration need.
+    pub fn read_write(mut self, read_write: bool) -> Self {
+        self.read_write = read_write;
+        self
+    }
+
+    pub fn open(self, path: impl AsRef<Path>) -> io::Result<Joystick> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(self.read_write)
+            .custom_flags(if self.nonblocking {
+                libc::O_NONBLOCK
+            } else {
+                0
+            })
+            .open(path)?;
+        let mut joystick = Joystick::from(Device::new_from_file(file)?);
+        joystick.path = Some(path.to_owned());
+        if self.grab {
+            joystick.grab()?;
+        }
+        Ok(joystick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_missing() {
+        let err = Joystick::builder()
+            .nonblocking(false)
+            .grab(true)
+            .read_write(false)
+            .open("/nonexistent/evdev-joystick")
+            .expect_err("Missing device can't be opened");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_open_options() {
+        use evdev_rs::enums::{EV_ABS, EventCode};
+
+        let Some((device, mut joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let devnode = device
+            .devnode()
+            .expect("Virtual joystick has a device node");
+        let grabbed = Joystick::builder()
+            .grab(true)
+            .read_write(false)
+            .open(devnode)
+            .expect("Virtual joystick can be opened");
+        assert_eq!(grabbed.path(), Some(Path::new(devnode)));
+        // Non-blocking by default, so an empty queue doesn't wait.
+        assert!(
+            grabbed
+                .try_next_event()
+                .expect("Device can be read")
+                .is_none()
+        );
+        let err = joystick.grab().expect_err("Device is already grabbed");
+        assert_eq!(err.raw_os_error(), Some(libc::EBUSY));
+        let abs_info = grabbed
+            .abs_info(&EventCode::EV_ABS(EV_ABS::ABS_RZ))
+            .expect("Harness has ABS_RZ");
+        grabbed
+            .write_abs_info(EV_ABS::ABS_RZ, &abs_info)
+            .expect_err("Read-only device can't be written");
+    }
+}
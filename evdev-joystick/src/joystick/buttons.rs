@@ -0,0 +1,65 @@
+use std::{collections::BTreeMap, io};
+
+use evdev_rs::enums::{EV_KEY, EventCode, EventType, int_to_ev_key};
+use libc::c_ulong;
+
+use super::Joystick;
+use crate::raw;
+
+impl Joystick {
+    /// The indices of every button currently held down, read from `EVIOCGKEY`. Useful to
+    /// initialise state when starting while buttons are already pressed.
+    pub fn pressed_buttons(&self) -> io::Result<Vec<u32>> {
+        Ok(self
+            .button_states()?
+            .into_iter()
+            .filter_map(|(index, pressed)| pressed.then_some(index))
+            .collect())
+    }
+
+    /// Whether each button is currently held, keyed by the same index as
+    /// [`Joystick::get_button_index`] returns. Read from `EVIOCGKEY`.
+    pub fn button_states(&self) -> io::Result<BTreeMap<u32, bool>> {
+        let bits = self.key_bits()?;
+        Ok(self
+            .buttons
+            .iter()
+            .map(|(&code, &index)| (index, raw::test_bit(&bits, code as usize)))
+            .collect())
+    }
+
+    /// The canonical name of a button, e.g. `BTN_SOUTH`, looked up from its index. `None` if
+    /// there is no such button or its code has no name.
+    pub fn button_name(&self, index: u32) -> Option<&'static str> {
+        raw::event_code_name(EventType::EV_KEY as u32, self.button_code(index)?)
+    }
+
+    /// The `EV_KEY` code of the button with the given index.
+    pub fn button_code(&self, index: u32) -> Option<u32> {
+        self.buttons
+            .iter()
+            .find(|&(_, &i)| i == index)
+            .map(|(&code, _)| code)
+    }
+
+    /// The [`EventCode`] of the button with the given index, the reverse of
+    /// [`Joystick::get_button_index`]. Codes without an `EV_KEY` variant are returned as
+    /// `EV_UNK`.
+    pub fn button_event_code(&self, index: u32) -> Option<EventCode> {
+        let code = self.button_code(index)?;
+        Some(match int_to_ev_key(code) {
+            Some(key) => EventCode::EV_KEY(key),
+            None => EventCode::EV_UNK {
+                event_type: EventType::EV_KEY as u32,
+                event_code: code,
+            },
+        })
+    }
+
+    fn key_bits(&self) -> io::Result<Vec<c_ulong>> {
+        let mut bits = raw::bitmask(EV_KEY::KEY_MAX as usize);
+        // SAFETY: the buffer's length is passed along with it.
+        unsafe { raw::evioc_get_key(self.fd(), &mut bits) }?;
+        Ok(bits)
+    }
+}
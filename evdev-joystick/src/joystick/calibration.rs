@@ -0,0 +1,447 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    time::{Duration, Instant},
+};
+#[cfg(feature = "toml")]
+use std::{fs, path::Path};
+
+use evdev_rs::{
+    AbsInfo,
+    enums::{EV_ABS, EventCode},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Joystick, JoystickAbsInfo, NormalizeOptions,
+    names::{axis_from_name, axis_name},
+};
+
+/// Mirror of the kernel's `input_absinfo`, convertible to and from [`AbsInfo`].
+///
+/// With the `serde` feature enabled this can be serialized, so an axis' calibration can be
+/// saved and later restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AxisInfo {
+    pub value: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub fuzz: i32,
+    pub flat: i32,
+    pub resolution: i32,
+}
+
+impl From<AbsInfo> for AxisInfo {
+    fn from(abs_info: AbsInfo) -> Self {
+        let AbsInfo {
+            value,
+            minimum,
+            maximum,
+            fuzz,
+            flat,
+            resolution,
+        } = abs_info;
+        AxisInfo {
+            value,
+            minimum,
+            maximum,
+            fuzz,
+            flat,
+            resolution,
+        }
+    }
+}
+
+impl From<AxisInfo> for AbsInfo {
+    fn from(axis_info: AxisInfo) -> Self {
+        let AxisInfo {
+            value,
+            minimum,
+            maximum,
+            fuzz,
+            flat,
+            resolution,
+        } = axis_info;
+        AbsInfo {
+            value,
+            minimum,
+            maximum,
+            fuzz,
+            flat,
+            resolution,
+        }
+    }
+}
+
+impl From<&JoystickAbsInfo> for AxisInfo {
+    fn from(abs_info: &JoystickAbsInfo) -> Self {
+        AxisInfo::from(abs_info.0)
+    }
+}
+
+impl From<AxisInfo> for JoystickAbsInfo {
+    fn from(axis_info: AxisInfo) -> Self {
+        JoystickAbsInfo(axis_info.into())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for JoystickAbsInfo {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        abs_info_serde::serialize(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for JoystickAbsInfo {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        abs_info_serde::deserialize(deserializer).map(JoystickAbsInfo)
+    }
+}
+
+/// (De)serializes evdev-rs' [`AbsInfo`], which has no serde support of its own, in the shape
+/// of an [`AxisInfo`]. Use it as `#[serde(with = "evdev_joystick::abs_info_serde")]` on
+/// `AbsInfo` fields.
+#[cfg(feature = "serde")]
+pub mod abs_info_serde {
+    use evdev_rs::AbsInfo;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::AxisInfo;
+
+    pub fn serialize<S: Serializer>(abs_info: &AbsInfo, serializer: S) -> Result<S::Ok, S::Error> {
+        AxisInfo::from(*abs_info).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AbsInfo, D::Error> {
+        AxisInfo::deserialize(deserializer).map(AbsInfo::from)
+    }
+}
+
+/// Calibration of every absolute axis of a device, keyed by axis name (e.g. `ABS_X`) so a
+/// profile maps onto another device by meaning rather than by index.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Calibration {
+    pub axes: BTreeMap<String, AxisInfo>,
+    /// Axes whose direction is reversed when normalizing, e.g. throttles reporting "forward"
+    /// as their minimum.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inverted: BTreeSet<String>,
+}
+
+impl Calibration {
+    /// Writes the profile to `path` as TOML.
+    #[cfg(feature = "toml")]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let toml = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, toml)
+    }
+
+    /// Reads a profile written by [`Calibration::save`]. Malformed files fail with
+    /// [`io::ErrorKind::InvalidData`].
+    #[cfg(feature = "toml")]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let toml = fs::read_to_string(path)?;
+        toml::from_str(&toml).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The [`NormalizeOptions`] for `axis`, inverted if the profile says so.
+    pub fn normalize_options(&self, axis: EV_ABS) -> NormalizeOptions {
+        NormalizeOptions {
+            invert: self.inverted.contains(axis_name(axis)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Outcome of [`Joystick::apply_calibration`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AppliedCalibration {
+    pub applied: Vec<EV_ABS>,
+    /// Axes in the profile which are unknown or missing on the device.
+    pub skipped: Vec<String>,
+}
+
+impl Joystick {
+    pub fn export_calibration(&self) -> Calibration {
+        let axes = self
+            .abs_axis()
+            .filter_map(|axis| {
+                let abs_info = self.abs_info_for(axis)?;
+                Some((axis_name(axis).to_owned(), AxisInfo::from(&abs_info)))
+            })
+            .collect();
+        Calibration {
+            axes,
+            inverted: BTreeSet::new(),
+        }
+    }
+
+    /// Writes the range, fuzz, flat and resolution of each axis in `calibration` to the device
+    /// with [`Joystick::write_abs_info`], leaving the current axis values untouched.
+    ///
+    /// Stops at the first axis that can't be written, e.g. because the device was opened
+    /// read-only or the profile has an empty range, returning an error naming the axis. Axes
+    /// before it stay written.
+    pub fn apply_calibration(&self, calibration: &Calibration) -> io::Result<AppliedCalibration> {
+        let mut result = AppliedCalibration::default();
+        for (name, axis_info) in &calibration.axes {
+            let Some(axis) = axis_from_name(name).filter(|axis| self.abs_axis.contains(axis))
+            else {
+                result.skipped.push(name.clone());
+                continue;
+            };
+            let Some(current) = self.abs_info_for(axis) else {
+                result.skipped.push(name.clone());
+                continue;
+            };
+            let abs_info = JoystickAbsInfo(AbsInfo {
+                value: current.value,
+                ..AbsInfo::from(*axis_info)
+            });
+            self.write_abs_info(axis, &abs_info)
+                .map_err(|e| io::Error::new(e.kind(), format!("{name}: {e}")))?;
+            result.applied.push(axis);
+        }
+        Ok(result)
+    }
+
+    /// Restores the range, fuzz, flat and resolution `axis` had when the device was opened,
+    /// undoing later calls such as [`Joystick::write_abs_info`] or
+    /// [`Joystick::apply_calibration`].
+    ///
+    /// evdev keeps no factory defaults: the baseline is whatever the kernel reported on open,
+    /// so changes made by another process before that stay until the device is replugged. To
+    /// restore a known-good baseline instead, save it with [`Joystick::export_calibration`] and
+    /// reapply it with [`Joystick::apply_calibration`]. Fails with
+    /// [`io::ErrorKind::NotFound`] if the device has no such axis.
+    pub fn reset_calibration(&self, axis: EV_ABS) -> io::Result<()> {
+        let (Some(baseline), Some(current)) = (self.baseline.get(&axis), self.abs_info_for(axis))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("joystick has no axis {}", axis_name(axis)),
+            ));
+        };
+        let abs_info = JoystickAbsInfo(AbsInfo {
+            value: current.value,
+            ..*baseline
+        });
+        self.write_abs_info(axis, &abs_info)
+    }
+
+    /// [`Joystick::reset_calibration`] for every axis.
+    pub fn reset_all_calibration(&self) -> io::Result<()> {
+        self.baseline
+            .keys()
+            .try_for_each(|&axis| self.reset_calibration(axis))
+    }
+
+    /// Reads events for `duration` and returns the smallest and largest value each axis
+    /// reached, for calibrating sticks whose factory range is off. Prompt the user to move
+    /// every axis to its extremes meanwhile.
+    ///
+    /// Axes that didn't move are left out rather than collapsed to a single value, so
+    /// [`Joystick::apply_ranges`] keeps their factory range.
+    pub fn calibrate_range(&self, duration: Duration) -> io::Result<BTreeMap<EV_ABS, (i32, i32)>> {
+        let deadline = Instant::now() + duration;
+        let mut tracker = RangeTracker::new(
+            self.all_abs_info()
+                .into_iter()
+                .map(|(axis, abs_info)| (axis, abs_info.value))
+                .collect(),
+        );
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            let Some(event) = self.next_event_timeout(remaining)? else {
+                break;
+            };
+            if let EventCode::EV_ABS(axis) = event.event_code {
+                tracker.observe(axis, event.value);
+            }
+        }
+        Ok(tracker.finish())
+    }
+
+    /// Writes ranges found by [`Joystick::calibrate_range`] to the device, keeping each axis'
+    /// fuzz, flat and resolution.
+    pub fn apply_ranges(&self, ranges: &BTreeMap<EV_ABS, (i32, i32)>) -> io::Result<()> {
+        for (&axis, &(minimum, maximum)) in ranges {
+            let Some(mut abs_info) = self.abs_info_for(axis) else {
+                continue;
+            };
+            abs_info.set_range(minimum, maximum);
+            self.write_abs_info(axis, &abs_info)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the calibration of every axis to `path` as TOML, see [`Calibration::save`].
+    #[cfg(feature = "toml")]
+    pub fn save_calibration(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.export_calibration().save(path)
+    }
+
+    /// Loads a profile saved with [`Joystick::save_calibration`] and applies it. Axes the
+    /// device lacks are skipped and listed in the result.
+    #[cfg(feature = "toml")]
+    pub fn load_calibration(&self, path: impl AsRef<Path>) -> io::Result<AppliedCalibration> {
+        self.apply_calibration(&Calibration::load(path)?)
+    }
+}
+
+/// The range each axis covered, starting from the value it had when tracking began.
+struct RangeTracker {
+    start: BTreeMap<EV_ABS, i32>,
+    ranges: BTreeMap<EV_ABS, (i32, i32)>,
+}
+
+impl RangeTracker {
+    fn new(start: BTreeMap<EV_ABS, i32>) -> Self {
+        RangeTracker {
+            start,
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    fn observe(&mut self, axis: EV_ABS, value: i32) {
+        let start = self.start.get(&axis).copied().unwrap_or(value);
+        let (minimum, maximum) = self.ranges.entry(axis).or_insert((start, start));
+        *minimum = (*minimum).min(value);
+        *maximum = (*maximum).max(value);
+    }
+
+    /// The ranges of the axes that moved.
+    fn finish(mut self) -> BTreeMap<EV_ABS, (i32, i32)> {
+        self.ranges
+            .retain(|_, (minimum, maximum)| minimum < maximum);
+        self.ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let axis_info = AxisInfo {
+            value: 12,
+            minimum: -512,
+            maximum: 511,
+            fuzz: 4,
+            flat: 32,
+            resolution: 3,
+        };
+        let abs_info = AbsInfo::from(axis_info);
+        assert_eq!(abs_info.minimum, -512);
+        assert_eq!(abs_info.resolution, 3);
+        assert_eq!(AxisInfo::from(abs_info), axis_info);
+        assert_eq!(AxisInfo::from(&JoystickAbsInfo::from(axis_info)), axis_info);
+    }
+
+    #[test]
+    fn test_inverted_axes() {
+        let calibration = Calibration {
+            inverted: BTreeSet::from(["ABS_THROTTLE".to_owned()]),
+            ..Default::default()
+        };
+        let throttle = calibration.normalize_options(EV_ABS::ABS_THROTTLE);
+        assert!(throttle.invert);
+        assert!(!calibration.normalize_options(EV_ABS::ABS_X).invert);
+        let axis = JoystickAbsInfo::from(AxisInfo {
+            value: 0,
+            maximum: 255,
+            ..Default::default()
+        });
+        assert_eq!(axis.normalized_with(throttle), i16::MAX);
+    }
+
+    #[test]
+    fn test_range_tracker() {
+        let mut tracker = RangeTracker::new(BTreeMap::from([
+            (EV_ABS::ABS_X, 128),
+            (EV_ABS::ABS_Y, 128),
+            (EV_ABS::ABS_Z, 0),
+        ]));
+        for value in [140, 250, 3, 130] {
+            tracker.observe(EV_ABS::ABS_X, value);
+        }
+        // Reported without moving, e.g. repeated after a SYN_DROPPED.
+        tracker.observe(EV_ABS::ABS_Y, 128);
+        tracker.observe(EV_ABS::ABS_RX, 10);
+        tracker.observe(EV_ABS::ABS_RX, 20);
+        assert_eq!(
+            tracker.finish(),
+            BTreeMap::from([(EV_ABS::ABS_X, (3, 250)), (EV_ABS::ABS_RX, (10, 20))])
+        );
+    }
+
+    #[test]
+    fn test_apply_calibration() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let rz = AxisInfo {
+            minimum: 0,
+            maximum: 255,
+            flat: 8,
+            ..Default::default()
+        };
+        let mut calibration = Calibration {
+            axes: BTreeMap::from([("ABS_RZ".to_owned(), rz), ("ABS_WHEEL".to_owned(), rz)]),
+            ..Default::default()
+        };
+        let applied = joystick
+            .apply_calibration(&calibration)
+            .expect("Calibration can be applied");
+        assert_eq!(applied.applied, [EV_ABS::ABS_RZ]);
+        assert_eq!(applied.skipped, ["ABS_WHEEL"]);
+        let abs_info = joystick.abs_info_for(EV_ABS::ABS_RZ).expect("Axis exists");
+        assert_eq!(abs_info.flat, 8);
+        // A failed write is reported rather than counted as applied.
+        calibration
+            .axes
+            .insert("ABS_RZ".to_owned(), AxisInfo { maximum: 0, ..rz });
+        let err = joystick
+            .apply_calibration(&calibration)
+            .expect_err("Empty range is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().starts_with("ABS_RZ: "), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_save_load() {
+        let calibration = Calibration {
+            axes: BTreeMap::from([(
+                "ABS_X".to_owned(),
+                AxisInfo {
+                    minimum: -512,
+                    maximum: 511,
+                    flat: 16,
+                    ..Default::default()
+                },
+            )]),
+            inverted: BTreeSet::from(["ABS_X".to_owned()]),
+        };
+        let path = std::env::temp_dir().join(format!(
+            "evdev-joystick-calibration-{}.toml",
+            std::process::id()
+        ));
+        calibration.save(&path).expect("Profile can be saved");
+        let loaded = Calibration::load(&path).expect("Profile can be loaded");
+        assert_eq!(loaded, calibration);
+
+        fs::write(&path, "axes = 3").expect("Fixture can be written");
+        let err = Calibration::load(&path).expect_err("Profile is malformed");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(path).expect("Fixture can be removed");
+    }
+}
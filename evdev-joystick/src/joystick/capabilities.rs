@@ -0,0 +1,101 @@
+use evdev_rs::enums::EventCode;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{AxisInfo, InputId, Joystick, axis_name};
+
+/// Everything a device reports about itself, much like `evtest` prints on startup. With the
+/// `serde` feature enabled it can be serialized, e.g. to attach to a bug report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capabilities {
+    pub name: Option<String>,
+    pub id: InputId,
+    /// The evdev protocol version of the driver, packed like [`Joystick::driver_version`].
+    pub driver_version: i32,
+    pub abs_axes: Vec<AbsAxisCapability>,
+    pub buttons: Vec<ButtonCapability>,
+    /// Relative axis names, e.g. `REL_WHEEL`.
+    pub rel_axes: Vec<String>,
+    /// LED names, e.g. `LED_NUML`.
+    pub leds: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AbsAxisCapability {
+    /// The axis name, e.g. `ABS_X`.
+    pub name: String,
+    pub info: AxisInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ButtonCapability {
+    /// The index [`Joystick::get_button_index`] returns for the button.
+    pub index: u32,
+    pub code: u32,
+    /// The button name, e.g. `BTN_SOUTH`, if the code has one.
+    pub name: Option<String>,
+}
+
+impl Joystick {
+    /// A snapshot of the device's identity and capabilities, with each axis' current
+    /// `AbsInfo`. Everything is listed in ascending code order.
+    pub fn capabilities(&self) -> Capabilities {
+        let abs_axes = self
+            .abs_axis()
+            .filter_map(|axis| {
+                let abs_info = self.abs_info_for(axis)?;
+                Some(AbsAxisCapability {
+                    name: axis_name(axis).to_owned(),
+                    info: AxisInfo::from(&abs_info),
+                })
+            })
+            .collect();
+        let buttons = self
+            .buttons
+            .iter()
+            .map(|(&code, &index)| ButtonCapability {
+                index,
+                code,
+                name: self.button_name(index).map(str::to_owned),
+            })
+            .collect();
+        Capabilities {
+            name: self.name(),
+            id: self.input_id(),
+            driver_version: self.device.driver_version(),
+            abs_axes,
+            buttons,
+            rel_axes: self
+                .rel_axis()
+                .map(|rel| EventCode::EV_REL(rel).to_string())
+                .collect(),
+            leds: self
+                .leds()
+                .map(|led| EventCode::EV_LED(led).to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_capabilities() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let capabilities = joystick.capabilities();
+        assert_eq!(
+            capabilities.driver_version,
+            joystick
+                .driver_version()
+                .expect("Driver version can be read")
+        );
+        assert_eq!(capabilities.id, joystick.input_id());
+        assert_eq!(capabilities.abs_axes.len(), 3);
+        assert_eq!(capabilities.buttons.len(), 3);
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    fmt, io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use evdev_rs::{InputEvent, TimeVal};
+
+use super::Joystick;
+
+/// The clock used to timestamp a device's events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ClockId {
+    /// Wall-clock time, which jumps when the system clock is adjusted. The kernel's default.
+    #[default]
+    Realtime,
+    /// Time since boot, excluding suspend. Suitable for measuring deltas between events.
+    Monotonic,
+    /// Like [`ClockId::Monotonic`], but keeps counting while the system is suspended.
+    Boottime,
+}
+
+impl ClockId {
+    pub fn as_raw(self) -> libc::clockid_t {
+        match self {
+            ClockId::Realtime => libc::CLOCK_REALTIME,
+            ClockId::Monotonic => libc::CLOCK_MONOTONIC,
+            ClockId::Boottime => libc::CLOCK_BOOTTIME,
+        }
+    }
+}
+
+/// An event timestamp, displayed as seconds with all six digits of microseconds, e.g.
+/// `12.000005`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventTime(pub TimeVal);
+
+impl EventTime {
+    /// The time since the clock's epoch: 1970 for [`ClockId::Realtime`], boot for the others.
+    /// Times before the epoch are clamped to zero.
+    pub fn as_duration(self) -> Duration {
+        let TimeVal { tv_sec, tv_usec } = self.0;
+        Duration::from_secs(tv_sec.max(0) as u64) + Duration::from_micros(tv_usec.max(0) as u64)
+    }
+
+    /// The time as a [`SystemTime`]. Only meaningful with the default [`ClockId::Realtime`].
+    pub fn as_system_time(self) -> SystemTime {
+        UNIX_EPOCH + self.as_duration()
+    }
+}
+
+impl fmt::Display for EventTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:06}", self.0.tv_sec, self.0.tv_usec)
+    }
+}
+
+/// The timestamp of `event` as a [`Duration`], see [`EventTime::as_duration`].
+pub fn event_time_as_duration(event: &InputEvent) -> Duration {
+    EventTime(event.time).as_duration()
+}
+
+/// The timestamp of `event` as a [`SystemTime`], see [`EventTime::as_system_time`].
+pub fn event_time_as_system_time(event: &InputEvent) -> SystemTime {
+    EventTime(event.time).as_system_time()
+}
+
+impl Joystick {
+    /// Selects the clock used for event timestamps with `EVIOCSCLOCKID`. This affects every
+    /// event read from the device afterwards; events already queued with the old clock are
+    /// discarded by the kernel, which reports a `SYN_DROPPED` in their place. Goes through
+    /// libevdev so the events it synthesizes while resyncing use the same clock.
+    ///
+    /// The clock belongs to the open file, so it changes for everything reading through this
+    /// fd, including duplicates of it, but not for other opens of the same device.
+    pub fn set_clock(&self, clock: ClockId) -> io::Result<()> {
+        self.device.set_clock_id(clock.as_raw())
+    }
+
+    /// Shorthand for `set_clock(ClockId::Monotonic)`, so timestamps can be used for deltas.
+    pub fn set_clock_monotonic(&self) -> io::Result<()> {
+        self.set_clock(ClockId::Monotonic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev_rs::enums::{EV_KEY, EventCode};
+
+    use super::*;
+
+    #[test]
+    fn test_event_time() {
+        let event = InputEvent::new(
+            &TimeVal::new(12, 5),
+            &EventCode::EV_KEY(EV_KEY::BTN_TRIGGER),
+            1,
+        );
+        assert_eq!(EventTime(event.time).to_string(), "12.000005");
+        assert_eq!(
+            event_time_as_duration(&event),
+            Duration::from_secs(12) + Duration::from_micros(5)
+        );
+        assert_eq!(
+            event_time_as_system_time(&event)
+                .duration_since(UNIX_EPOCH)
+                .unwrap(),
+            Duration::new(12, 5_000)
+        );
+        assert_eq!(EventTime(TimeVal::new(-1, 0)).as_duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_set_clock_monotonic() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        joystick
+            .set_clock_monotonic()
+            .expect("Clock can be changed");
+        device
+            .emit(&[InputEvent::new(
+                &TimeVal::new(0, 0),
+                &EventCode::EV_KEY(EV_KEY::BTN_TRIGGER),
+                1,
+            )])
+            .expect("Event can be emitted");
+        let event = joystick
+            .next_event_timeout(Duration::from_secs(1))
+            .expect("Event is read without error")
+            .expect("Emitted event is read");
+        let mut now = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `now` is a valid timespec for the duration of the call.
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+        // Monotonic time counts from boot, so would be decades off had the clock not changed.
+        assert!(
+            (now.tv_sec - event.time.tv_sec).abs() <= 1,
+            "{:?}",
+            event.time
+        );
+    }
+}
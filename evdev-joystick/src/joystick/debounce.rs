@@ -0,0 +1,205 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io,
+    time::Duration,
+};
+
+use evdev_rs::TimeVal;
+
+use super::{EventTime, Joystick, JoystickEvent, TypedJoystickEvents};
+
+#[derive(Debug, Clone, Copy)]
+struct ButtonDebounce {
+    reported: bool,
+    // Event time of the last reported change, which starts the window.
+    reported_at: Duration,
+    pressed: bool,
+    pressed_at: TimeVal,
+}
+
+/// Suppresses button chatter: after a button's reported state changes, further changes
+/// within `window` are held back, and only reported once the window has passed if the
+/// button settled in a different state. A release and press (or press and release) within
+/// the window therefore cancel out. Times are those of the events, so this works the same
+/// however late events are read. Events other than button changes pass through untouched.
+#[derive(Debug, Clone)]
+pub struct Debouncer {
+    window: Duration,
+    buttons: BTreeMap<u32, ButtonDebounce>,
+    ready: VecDeque<JoystickEvent>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Debouncer {
+            window,
+            buttons: BTreeMap::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Feeds an event, returning the events that are ready to report, in order.
+    pub fn push(&mut self, event: JoystickEvent) -> impl Iterator<Item = JoystickEvent> + '_ {
+        let (now, window) = (EventTime(event.time()).as_duration(), self.window);
+        self.settle(|debounce| now >= debounce.reported_at + window);
+        match event {
+            JoystickEvent::ButtonChanged {
+                time,
+                index,
+                pressed,
+            } => match self.buttons.get_mut(&index) {
+                Some(debounce) => {
+                    debounce.pressed = pressed;
+                    debounce.pressed_at = time;
+                    if pressed != debounce.reported && now >= debounce.reported_at + window {
+                        debounce.reported = pressed;
+                        debounce.reported_at = now;
+                        self.ready.push_back(event);
+                    }
+                }
+                None => {
+                    self.buttons.insert(
+                        index,
+                        ButtonDebounce {
+                            reported: pressed,
+                            reported_at: now,
+                            pressed,
+                            pressed_at: time,
+                        },
+                    );
+                    self.ready.push_back(event);
+                }
+            },
+            _ => self.ready.push_back(event),
+        }
+        self.ready.drain(..)
+    }
+
+    /// Reports every button whose state differs from what was last reported, without waiting
+    /// for its window to pass. Used once no more events have arrived for a window.
+    pub fn flush(&mut self) -> impl Iterator<Item = JoystickEvent> + '_ {
+        self.settle(|_| true);
+        self.ready.drain(..)
+    }
+
+    /// Whether a button is held back, so [`Debouncer::flush`] would report something.
+    pub fn has_pending(&self) -> bool {
+        self.buttons
+            .values()
+            .any(|debounce| debounce.pressed != debounce.reported)
+    }
+
+    fn settle(&mut self, expired: impl Fn(&ButtonDebounce) -> bool) {
+        for (&index, debounce) in &mut self.buttons {
+            if debounce.pressed != debounce.reported && expired(debounce) {
+                debounce.reported = debounce.pressed;
+                debounce.reported_at = EventTime(debounce.pressed_at).as_duration();
+                self.ready.push_back(JoystickEvent::ButtonChanged {
+                    time: debounce.pressed_at,
+                    index,
+                    pressed: debounce.pressed,
+                });
+            }
+        }
+    }
+}
+
+/// Typed events with button chatter suppressed, see [`Joystick::debounced_events`].
+pub struct DebouncedEvents<'a> {
+    joystick: &'a Joystick,
+    events: TypedJoystickEvents<'a>,
+    debouncer: Debouncer,
+    ready: VecDeque<JoystickEvent>,
+}
+
+impl Joystick {
+    /// Like [`Joystick::typed_events`], with button changes debounced by a [`Debouncer`].
+    /// While a button is held back, the iterator waits at most `window` for the next event
+    /// before reporting the state the button settled in.
+    pub fn debounced_events(&self, window: Duration) -> DebouncedEvents<'_> {
+        DebouncedEvents {
+            joystick: self,
+            events: self.typed_events(),
+            debouncer: Debouncer::new(window),
+            ready: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for DebouncedEvents<'_> {
+    type Item = io::Result<JoystickEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.ready.pop_front() {
+                return Some(Ok(event));
+            }
+            let event = if self.debouncer.has_pending() {
+                match self.joystick.next_event_timeout(self.debouncer.window) {
+                    Ok(Some(event)) => self.joystick.classify_event(event),
+                    Ok(None) => {
+                        self.ready.extend(self.debouncer.flush());
+                        continue;
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+            } else {
+                match self.events.next()? {
+                    Ok(event) => event,
+                    Err(e) => return Some(Err(e)),
+                }
+            };
+            self.ready.extend(self.debouncer.push(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button(millis: i64, index: u32, pressed: bool) -> JoystickEvent {
+        JoystickEvent::ButtonChanged {
+            time: TimeVal::new(millis / 1000, millis % 1000 * 1000),
+            index,
+            pressed,
+        }
+    }
+
+    #[test]
+    fn test_debouncer() {
+        let mut debouncer = Debouncer::new(Duration::from_millis(5));
+        let mut push = |event| debouncer.push(event).collect::<Vec<_>>();
+        assert_eq!(push(button(1000, 0, true)), [button(1000, 0, true)]);
+        // Chatter right after the press cancels out.
+        assert_eq!(push(button(1001, 0, false)), []);
+        assert_eq!(push(button(1002, 0, true)), []);
+        // Other buttons are debounced separately.
+        assert_eq!(push(button(1003, 1, true)), [button(1003, 1, true)]);
+        // A change within the window is reported once an event shows the window passed.
+        assert_eq!(push(button(1006, 1, false)), []);
+        assert_eq!(
+            push(button(1010, 0, false)),
+            [button(1006, 1, false), button(1010, 0, false)]
+        );
+        assert_eq!(push(button(1012, 0, true)), []);
+        let sync = JoystickEvent::Sync {
+            time: TimeVal::new(1, 20_000),
+        };
+        assert_eq!(push(sync.clone()), [button(1012, 0, true), sync]);
+    }
+
+    #[test]
+    fn test_debouncer_flush() {
+        let mut debouncer = Debouncer::new(Duration::from_millis(5));
+        debouncer.push(button(1000, 0, true)).for_each(drop);
+        debouncer.push(button(1001, 0, false)).for_each(drop);
+        assert!(debouncer.has_pending());
+        assert_eq!(
+            debouncer.flush().collect::<Vec<_>>(),
+            [button(1001, 0, false)]
+        );
+        assert!(!debouncer.has_pending());
+        assert_eq!(debouncer.flush().count(), 0);
+    }
+}
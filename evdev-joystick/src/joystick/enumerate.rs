@@ -0,0 +1,232 @@
+use std::{
+    fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use evdev_rs::{
+    DeviceWrapper,
+    enums::{EV_KEY, InputProp},
+};
+
+use super::Joystick;
+
+// Covers both the BTN_JOYSTICK (BTN_TRIGGER..) and BTN_GAMEPAD (BTN_SOUTH..) blocks.
+const JOYSTICK_BUTTONS: RangeInclusive<u32> =
+    EV_KEY::BTN_TRIGGER as u32..=EV_KEY::BTN_THUMBR as u32;
+
+impl Joystick {
+    /// Lists the joysticks in `/dev/input/by-id/`, falling back to
+    /// [`Joystick::joysticks_from_dev`] when the directory is missing or has no joystick
+    /// entries, as in containers or with udev rules that create no by-id links. Devices are
+    /// opened as the iterator reaches them.
+    pub fn joysticks() -> io::Result<impl Iterator<Item = io::Result<Joystick>>> {
+        joysticks_or_else(PathBuf::from("/dev/input/by-id/"), || {
+            Ok(Box::new(Self::joysticks_from_dev()?))
+        })
+    }
+
+    /// Lists the `*-event-joystick` entries of `dir`, a directory laid out like
+    /// `/dev/input/by-id/`. Use [`Joystick::enumerate_in`] to match other entries.
+    pub fn joysticks_in(
+        dir: impl AsRef<Path>,
+    ) -> io::Result<impl Iterator<Item = io::Result<Joystick>>> {
+        Self::enumerate_in(dir, is_by_id_joystick)
+    }
+
+    /// Opens every entry of `dir` whose path matches `filter`. Entries that can't be read or
+    /// opened are yielded as errors, which name the path but keep the original error kind
+    /// (e.g. [`io::ErrorKind::PermissionDenied`]).
+    pub fn enumerate_in(
+        dir: impl AsRef<Path>,
+        filter: impl Fn(&Path) -> bool,
+    ) -> io::Result<impl Iterator<Item = io::Result<Joystick>>> {
+        Ok(fs::read_dir(dir)?.filter_map(move |entry| match entry {
+            Ok(entry) => {
+                let path = entry.path();
+                filter(&path).then(|| open_listed(&path))
+            }
+            Err(e) => Some(Err(e)),
+        }))
+    }
+
+    /// Opens every `/dev/input/event*` node, keeping those that
+    /// [look like joysticks](Joystick::looks_like_joystick). Unlike [`Joystick::joysticks`]
+    /// this works without udev's by-id symlinks. Nodes that can't be opened are yielded as
+    /// errors naming the path, like [`Joystick::enumerate_in`].
+    pub fn joysticks_from_dev() -> io::Result<impl Iterator<Item = io::Result<Joystick>>> {
+        let mut nodes = fs::read_dir("/dev/input/")?
+            .filter_map(|entry| match entry {
+                Ok(entry) => {
+                    let number = entry
+                        .file_name()
+                        .to_str()?
+                        .strip_prefix("event")?
+                        .parse::<u32>()
+                        .ok()?;
+                    Some(Ok((number, entry.path())))
+                }
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<io::Result<Vec<(u32, PathBuf)>>>()?;
+        nodes.sort_unstable();
+        Ok(nodes
+            .into_iter()
+            .filter_map(|(_, path)| match open_listed(&path) {
+                Ok(joystick) if joystick.looks_like_joystick() => Some(Ok(joystick)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }))
+    }
+
+    /// Whether the device's capabilities are those of a joystick or gamepad: an absolute
+    /// axis along with a joystick or gamepad button, or `BTN_JOYSTICK`/`BTN_GAMEPAD` alone
+    /// for digital-only pads. Accelerometers are excluded even though they have axes.
+    pub fn looks_like_joystick(&self) -> bool {
+        if self
+            .device
+            .has_property(&InputProp::INPUT_PROP_ACCELEROMETER)
+        {
+            return false;
+        }
+        let has_button = |key: EV_KEY| self.buttons.contains_key(&(key as u32));
+        // BTN_JOYSTICK and BTN_GAMEPAD are aliases for the first button of each block.
+        has_button(EV_KEY::BTN_TRIGGER)
+            || has_button(EV_KEY::BTN_SOUTH)
+            || (!self.abs_axis.is_empty()
+                && self
+                    .buttons
+                    .keys()
+                    .any(|code| JOYSTICK_BUTTONS.contains(code)))
+    }
+}
+
+type Joysticks = Box<dyn Iterator<Item = io::Result<Joystick>>>;
+
+/// The joysticks of the by-id style `dir`, or those of `fallback` if it is missing or lists
+/// none.
+fn joysticks_or_else(
+    dir: PathBuf,
+    fallback: impl FnOnce() -> io::Result<Joysticks>,
+) -> io::Result<Joysticks> {
+    match Joystick::joysticks_in(dir) {
+        Ok(joysticks) => {
+            let mut joysticks = joysticks.peekable();
+            if joysticks.peek().is_some() {
+                Ok(Box::new(joysticks))
+            } else {
+                fallback()
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => fallback(),
+        Err(e) => Err(e),
+    }
+}
+
+fn open_listed(path: &Path) -> io::Result<Joystick> {
+    Joystick::new_from_path(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
+pub(super) fn is_by_id_joystick(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with("-event-joystick"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str, entries: &[&str]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("evdev-joystick-{}-{}", name, std::process::id()));
+        // Directories can't be opened as devices, so each match shows up as an error item.
+        for entry in entries {
+            fs::create_dir_all(dir.join(entry)).expect("Fixture directory can be created");
+        }
+        dir
+    }
+
+    #[test]
+    fn test_enumerate_in() {
+        let dir = fixture_dir(
+            "enumerate",
+            &[
+                "usb-pad-event-joystick",
+                "usb-pad-joystick",
+                "usb-kbd-event-kbd",
+            ],
+        );
+        let found = Joystick::joysticks_in(&dir)
+            .expect("Fixture directory is readable")
+            .collect::<Vec<_>>();
+        assert_eq!(found.len(), 1);
+        let err = found[0].as_ref().expect_err("Directory is not a device");
+        assert!(err.to_string().contains("usb-pad-event-joystick"), "{err}");
+        let none = Joystick::enumerate_in(&dir, |_| false)
+            .expect("Fixture directory is readable")
+            .count();
+        assert_eq!(none, 0);
+        fs::remove_dir_all(dir).expect("Fixture directory can be removed");
+    }
+
+    #[test]
+    #[ignore]
+    fn test_joysticks_from_dev() {
+        let joystick = Joystick::joysticks_from_dev()
+            .expect("/dev/input is readable")
+            .flatten()
+            .next()
+            .expect("No joystick was found, tests require a joystick be connected.");
+        assert!(joystick.looks_like_joystick());
+    }
+
+    #[test]
+    fn test_joysticks_from_dev_virtual() {
+        let Some((device, _joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let devnode = Path::new(
+            device
+                .devnode()
+                .expect("Virtual joystick has a device node"),
+        );
+        let found = Joystick::joysticks_from_dev()
+            .expect("/dev/input is readable")
+            .flatten()
+            .find(|joystick| joystick.path() == Some(devnode))
+            .expect("Virtual joystick is enumerated");
+        assert!(found.looks_like_joystick());
+    }
+
+    #[test]
+    fn test_joysticks_fallback() {
+        let fallback = || -> io::Result<Joysticks> { Ok(Box::new(std::iter::empty())) };
+        let fallback_used = |dir: &Path| {
+            let mut used = false;
+            joysticks_or_else(dir.to_owned(), || {
+                used = true;
+                fallback()
+            })
+            .expect("Fixture directory is readable")
+            .for_each(drop);
+            used
+        };
+        let empty = fixture_dir("fallback-empty", &["usb-kbd-event-kbd"]);
+        assert!(fallback_used(&empty));
+        let listed = fixture_dir("fallback-listed", &["usb-pad-event-joystick"]);
+        assert!(!fallback_used(&listed));
+        assert!(fallback_used(&empty.join("missing")));
+        fs::remove_dir_all(empty).expect("Fixture directory can be removed");
+        fs::remove_dir_all(listed).expect("Fixture directory can be removed");
+    }
+
+    #[test]
+    fn test_enumerate_missing_dir() {
+        let err = Joystick::enumerate_in("/nonexistent/evdev-joystick", |_| true)
+            .err()
+            .expect("Missing directory is an error");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,140 @@
+use std::io;
+
+use thiserror::Error;
+
+/// The device went away while it was being read, usually because it was unplugged.
+///
+/// Read errors caused by a disconnect carry this as their inner error, with the kind
+/// [`io::ErrorKind::NotConnected`]; see [`is_disconnected`].
+#[derive(Debug, Error)]
+#[error("joystick was disconnected")]
+pub struct DeviceDisconnected;
+
+/// A joystick failed and was removed from a [`JoystickSet`](super::JoystickSet), reported by
+/// [`JoystickSet::next_event`](super::JoystickSet::next_event) as the inner error of an
+/// error with the same kind as `source`; see [`removed_joystick`].
+#[derive(Debug, Error)]
+#[error("joystick {index} was removed from the set: {source}")]
+pub struct JoystickRemoved {
+    pub index: usize,
+    #[source]
+    pub source: io::Error,
+}
+
+/// A raw axis value outside the range the device declares for the axis, returned by
+/// [`JoystickAbsInfo::normalized_checked`](super::JoystickAbsInfo::normalized_checked).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("axis value {value} is outside its range {minimum}..={maximum}")]
+pub struct OutOfRange {
+    pub value: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+}
+
+/// An I/O error classified by whether the device went away, for callers that want to
+/// reconnect on [`JoystickError::Disconnected`] and give up on anything else.
+#[derive(Debug, Error)]
+pub enum JoystickError {
+    #[error("joystick was disconnected")]
+    Disconnected(#[source] io::Error),
+    #[error(transparent)]
+    Io(io::Error),
+}
+
+impl JoystickError {
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, JoystickError::Disconnected(_))
+    }
+
+    pub fn into_io_error(self) -> io::Error {
+        match self {
+            JoystickError::Disconnected(error) | JoystickError::Io(error) => error,
+        }
+    }
+}
+
+impl From<io::Error> for JoystickError {
+    fn from(error: io::Error) -> Self {
+        if is_disconnected(&error) {
+            JoystickError::Disconnected(error)
+        } else {
+            JoystickError::Io(error)
+        }
+    }
+}
+
+impl From<JoystickError> for io::Error {
+    fn from(error: JoystickError) -> Self {
+        error.into_io_error()
+    }
+}
+
+/// Whether `error` was caused by the device being disconnected.
+pub fn is_disconnected(error: &io::Error) -> bool {
+    let Some(inner) = error.get_ref() else {
+        return false;
+    };
+    match inner.downcast_ref::<JoystickRemoved>() {
+        Some(removed) => is_disconnected(&removed.source),
+        None => inner.is::<DeviceDisconnected>(),
+    }
+}
+
+/// The index of the joystick a [`JoystickSet`](super::JoystickSet) removed because of `error`.
+pub fn removed_joystick(error: &io::Error) -> Option<usize> {
+    error
+        .get_ref()?
+        .downcast_ref::<JoystickRemoved>()
+        .map(|removed| removed.index)
+}
+
+/// Turns the errors reported for a removed device (`ENODEV`, or `ENOENT` once its node is
+/// gone) into a [`DeviceDisconnected`] error.
+pub(crate) fn classify_read_error(error: io::Error) -> io::Error {
+    match error.raw_os_error() {
+        Some(libc::ENODEV | libc::ENOENT) => {
+            io::Error::new(io::ErrorKind::NotConnected, DeviceDisconnected)
+        }
+        _ => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_read_error() {
+        for errno in [libc::ENODEV, libc::ENOENT] {
+            let error = classify_read_error(io::Error::from_raw_os_error(errno));
+            assert!(is_disconnected(&error));
+            assert_eq!(error.kind(), io::ErrorKind::NotConnected);
+        }
+        let error = classify_read_error(io::Error::from_raw_os_error(libc::EIO));
+        assert_eq!(removed_joystick(&error), None);
+        assert!(!is_disconnected(&error));
+        assert_eq!(error.raw_os_error(), Some(libc::EIO));
+    }
+
+    #[test]
+    fn test_joystick_error() {
+        let error = JoystickError::from(classify_read_error(io::Error::from_raw_os_error(
+            libc::ENODEV,
+        )));
+        assert!(error.is_disconnected());
+        assert_eq!(error.to_string(), "joystick was disconnected");
+        assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotConnected);
+        let error = JoystickError::from(io::Error::from_raw_os_error(libc::EIO));
+        assert!(!error.is_disconnected());
+        assert_eq!(error.into_io_error().raw_os_error(), Some(libc::EIO));
+    }
+
+    #[test]
+    fn test_removed_joystick() {
+        let source = classify_read_error(io::Error::from_raw_os_error(libc::ENODEV));
+        let error = io::Error::new(source.kind(), JoystickRemoved { index: 2, source });
+        assert_eq!(removed_joystick(&error), Some(2));
+        assert!(is_disconnected(&error));
+        assert_eq!(error.kind(), io::ErrorKind::NotConnected);
+    }
+}
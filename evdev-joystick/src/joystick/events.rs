@@ -1,26 +1,324 @@
-use evdev_rs::{Device, InputEvent, ReadFlag, ReadStatus};
+use std::{
+    io,
+    os::fd::AsFd,
+    time::{Duration, Instant},
+};
 
-pub struct JoystickEvents<'a>(pub(crate) &'a Device);
+use evdev_rs::{
+    Device, InputEvent, ReadFlag, ReadStatus, TimeVal,
+    enums::{EV_ABS, EV_SYN, EventCode},
+};
+
+use nix::{
+    errno::Errno,
+    poll::{PollFd, PollFlags, PollTimeout, poll},
+};
+
+use super::{
+    HatDirection, Joystick,
+    error::classify_read_error,
+    hat::{hat_of_axis, is_dpad_button},
+};
+
+/// Iterator over a joystick's events.
+///
+/// A read error is yielded as the last item. If the device was unplugged that error is a
+/// [`DeviceDisconnected`](super::DeviceDisconnected).
+pub struct JoystickEvents<'a> {
+    device: &'a Device,
+    blocking: bool,
+    done: bool,
+    dropped: bool,
+}
+
+impl<'a> JoystickEvents<'a> {
+    /// In blocking mode the iterator waits for the next event, in non-blocking mode it ends
+    /// as soon as no event is available.
+    pub(crate) fn new(device: &'a Device, blocking: bool) -> Self {
+        JoystickEvents {
+            device,
+            blocking,
+            done: false,
+            dropped: false,
+        }
+    }
+
+    /// Whether events were dropped (`SYN_DROPPED`) since the last call.
+    pub(crate) fn take_dropped(&mut self) -> bool {
+        std::mem::take(&mut self.dropped)
+    }
+}
 
 impl<'a> Iterator for JoystickEvents<'a> {
-    type Item = InputEvent;
+    type Item = io::Result<InputEvent>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut read_flag = ReadFlag::NORMAL;
+        if self.done {
+            return None;
+        }
         loop {
-            match self.0.next_event(read_flag) {
-                Ok((status, event)) => match status {
-                    ReadStatus::Success => return Some(event),
-                    ReadStatus::Sync => read_flag = ReadFlag::SYNC,
-                },
-                Err(e) => match e.raw_os_error() {
-                    Some(libc::EAGAIN) => read_flag = ReadFlag::NORMAL,
-                    _ => {
-                        eprintln!("{}", e);
-                        return None;
+            match read_event_tracking_drops(self.device, &mut self.dropped) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) if self.blocking => {
+                    if let Err(e) = wait_readable(self.device, PollTimeout::NONE) {
+                        self.done = true;
+                        return Some(Err(e));
                     }
-                },
+                }
+                Ok(None) => return None,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// An [`InputEvent`] classified against the capabilities of the [`Joystick`] it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoystickEvent {
+    AxisMoved {
+        time: TimeVal,
+        axis: EV_ABS,
+        value: i32,
+        normalized: i16,
+    },
+    /// A button was pressed or released. `index` is the button's position as given by
+    /// [`Joystick::get_button_index`], and autorepeat events (value 2) count as pressed.
+    ButtonChanged {
+        time: TimeVal,
+        index: u32,
+        pressed: bool,
+    },
+    /// An `ABS_HAT*` axis or a `BTN_DPAD_*` button changed. D-pad buttons are reported as
+    /// hat 0, in place of [`JoystickEvent::ButtonChanged`].
+    HatMoved {
+        time: TimeVal,
+        hat: u8,
+        direction: HatDirection,
+    },
+    /// A `SYN_REPORT`, marking the end of a batch of changes that happened together.
+    Sync {
+        time: TimeVal,
+    },
+    Other(InputEvent),
+}
+
+impl JoystickEvent {
+    pub fn time(&self) -> TimeVal {
+        match self {
+            JoystickEvent::AxisMoved { time, .. }
+            | JoystickEvent::ButtonChanged { time, .. }
+            | JoystickEvent::HatMoved { time, .. }
+            | JoystickEvent::Sync { time } => *time,
+            JoystickEvent::Other(event) => event.time,
+        }
+    }
+}
+
+impl Joystick {
+    /// Reads the next event if one is already available, returning `Ok(None)` instead of
+    /// waiting when there is none. Suits loops that poll the device once per frame; the
+    /// device must be open in non-blocking mode, as it is by default.
+    pub fn try_next_event(&self) -> io::Result<Option<InputEvent>> {
+        read_event(&self.device)
+    }
+
+    /// Yields every event that is already available, ending once reading would block. Like
+    /// [`Joystick::try_next_event`] called until it returns `Ok(None)`.
+    pub fn drain_events(&self) -> impl Iterator<Item = io::Result<InputEvent>> + '_ {
+        self.events_nonblocking()
+    }
+
+    /// Waits up to `timeout` for the next event, returning `Ok(None)` if none arrived in time.
+    pub fn next_event_timeout(&self, timeout: Duration) -> io::Result<Option<InputEvent>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = read_event(&self.device)? {
+                return Ok(Some(event));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
             }
+            // Round up so a sub-millisecond remainder doesn't turn into a busy loop.
+            let timeout = PollTimeout::try_from(remaining.as_micros().div_ceil(1000))
+                .unwrap_or(PollTimeout::MAX);
+            wait_readable(&self.device, timeout)?;
         }
     }
+
+    pub fn classify_event(&self, event: InputEvent) -> JoystickEvent {
+        match event.event_code {
+            EventCode::EV_ABS(axis) if hat_of_axis(axis).is_some() => {
+                match hat_of_axis(axis).and_then(|hat| Some((hat, self.hat_direction(hat)?))) {
+                    Some((hat, direction)) => JoystickEvent::HatMoved {
+                        time: event.time,
+                        hat,
+                        direction,
+                    },
+                    None => JoystickEvent::Other(event),
+                }
+            }
+            EventCode::EV_KEY(key) if is_dpad_button(key) => JoystickEvent::HatMoved {
+                time: event.time,
+                hat: 0,
+                direction: self.dpad_direction(),
+            },
+            EventCode::EV_ABS(axis) => match self.abs_info(&event.event_code) {
+                Some(abs_info) => JoystickEvent::AxisMoved {
+                    time: event.time,
+                    axis,
+                    value: event.value,
+                    normalized: abs_info.normalized_value(),
+                },
+                None => JoystickEvent::Other(event),
+            },
+            EventCode::EV_SYN(EV_SYN::SYN_REPORT) => JoystickEvent::Sync { time: event.time },
+            _ => match self.get_button_index(&event.event_code) {
+                Some(index) => JoystickEvent::ButtonChanged {
+                    time: event.time,
+                    index,
+                    pressed: event.value != 0,
+                },
+                None => JoystickEvent::Other(event),
+            },
+        }
+    }
+}
+
+pub struct TypedJoystickEvents<'a> {
+    joystick: &'a Joystick,
+    events: JoystickEvents<'a>,
+}
+
+impl<'a> TypedJoystickEvents<'a> {
+    pub(crate) fn new(joystick: &'a Joystick, events: JoystickEvents<'a>) -> Self {
+        TypedJoystickEvents { joystick, events }
+    }
+}
+
+impl<'a> Iterator for TypedJoystickEvents<'a> {
+    type Item = io::Result<JoystickEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events
+            .next()
+            .map(|event| event.map(|event| self.joystick.classify_event(event)))
+    }
+}
+
+/// Shared body of the async streams: reads until an event is available, waiting on
+/// `poll_read_ready` whenever nothing is buffered. An error ends the stream.
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+pub(crate) fn poll_next_event(
+    device: &Device,
+    done: &mut bool,
+    mut poll_read_ready: impl FnMut() -> std::task::Poll<io::Result<()>>,
+) -> std::task::Poll<Option<io::Result<InputEvent>>> {
+    use std::task::Poll;
+
+    if *done {
+        return Poll::Ready(None);
+    }
+    loop {
+        let result = match read_event(device) {
+            Ok(Some(event)) => return Poll::Ready(Some(Ok(event))),
+            // Nothing buffered, wait for the fd to become readable before trying again.
+            Ok(None) => match poll_read_ready() {
+                Poll::Ready(Ok(())) => continue,
+                Poll::Ready(Err(e)) => Err(e),
+                Poll::Pending => return Poll::Pending,
+            },
+            Err(e) => Err(e),
+        };
+        *done = true;
+        return Poll::Ready(Some(result));
+    }
+}
+
+/// Waits until `device` has something to read or `timeout` passes. Returns early if
+/// interrupted by a signal, callers read again and wait again if there is still nothing.
+fn wait_readable(device: &Device, timeout: PollTimeout) -> io::Result<()> {
+    let mut fds = [PollFd::new(device.file().as_fd(), PollFlags::POLLIN)];
+    match poll(&mut fds, timeout) {
+        Ok(_) | Err(Errno::EINTR) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads the next available event, returning `None` if there is none yet.
+///
+/// A `SYN_DROPPED` is handled by draining the resync events, which libevdev has already
+/// applied to its view of the device state.
+pub(crate) fn read_event(device: &Device) -> io::Result<Option<InputEvent>> {
+    read_event_tracking_drops(device, &mut false)
+}
+
+/// Like [`read_event`], setting `dropped` if events were dropped and the device resynced.
+pub(crate) fn read_event_tracking_drops(
+    device: &Device,
+    dropped: &mut bool,
+) -> io::Result<Option<InputEvent>> {
+    let mut read_flag = ReadFlag::NORMAL;
+    loop {
+        match device.next_event(read_flag) {
+            Ok((status, event)) => match status {
+                ReadStatus::Success => return Ok(Some(event)),
+                ReadStatus::Sync => {
+                    if read_flag == ReadFlag::NORMAL {
+                        log::debug!("events were dropped, resyncing device state");
+                    }
+                    *dropped = true;
+                    read_flag = ReadFlag::SYNC
+                }
+            },
+            Err(e) => match e.raw_os_error() {
+                Some(libc::EAGAIN) if read_flag == ReadFlag::SYNC => read_flag = ReadFlag::NORMAL,
+                Some(libc::EAGAIN) => return Ok(None),
+                _ => return Err(classify_read_error(e)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use evdev_rs::enums::EV_KEY;
+
+    use super::*;
+
+    #[test]
+    fn test_events_wait_without_spinning() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let thumb = EventCode::EV_KEY(EV_KEY::BTN_THUMB);
+        let emitter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            device
+                .emit(&[InputEvent::new(&TimeVal::new(0, 0), &thumb, 1)])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+            device
+        });
+        let started = Instant::now();
+        let cpu_started = crate::joystick::tests::thread_cpu_time();
+        let event = joystick
+            .events()
+            .next()
+            .expect("Event is read")
+            .expect("Event is read");
+        let cpu_time = crate::joystick::tests::thread_cpu_time() - cpu_started;
+        assert_eq!((event.event_code, event.value), (thumb, 1));
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(
+            cpu_time < Duration::from_millis(100),
+            "Waiting took {cpu_time:?} of CPU time"
+        );
+        drop(emitter.join().expect("Emitter doesn't panic"));
+    }
 }
@@ -0,0 +1,162 @@
+use std::{fmt, io, io::Write, mem, os::fd::AsRawFd, ptr, time::Duration};
+
+use evdev_rs::{
+    DeviceWrapper, InputEvent, TimeVal,
+    enums::{EV_FF, EventCode},
+};
+
+use super::Joystick;
+use crate::raw;
+
+/// A force-feedback effect uploaded to a [`Joystick`]. The effect is removed from the
+/// device when this is dropped, freeing its slot.
+pub struct FfEffectId<'a> {
+    joystick: &'a Joystick,
+    id: i16,
+}
+
+impl FfEffectId<'_> {
+    pub fn id(&self) -> i16 {
+        self.id
+    }
+}
+
+impl fmt::Debug for FfEffectId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FfEffectId").field(&self.id).finish()
+    }
+}
+
+impl Drop for FfEffectId<'_> {
+    fn drop(&mut self) {
+        // Removal only fails if the device is gone, which frees the slot anyway.
+        let _ = self.joystick.remove_effect(self.id);
+    }
+}
+
+impl Joystick {
+    pub fn supports_rumble(&self) -> bool {
+        self.device.has(EventCode::EV_FF(EV_FF::FF_RUMBLE))
+    }
+
+    /// Uploads an `FF_RUMBLE` effect and starts playing it. The magnitudes are those of the
+    /// strong (low frequency) and weak (high frequency) motors.
+    pub fn rumble(&self, strong: u16, weak: u16, duration: Duration) -> io::Result<FfEffectId<'_>> {
+        let id = self.upload_rumble(-1, strong, weak, duration)?;
+        let effect = FfEffectId { joystick: self, id };
+        self.play_effect(id, true)?;
+        Ok(effect)
+    }
+
+    /// Replaces the magnitudes and duration of an uploaded rumble effect, reusing its slot,
+    /// and plays it again.
+    pub fn replace_rumble(
+        &self,
+        effect: &FfEffectId<'_>,
+        strong: u16,
+        weak: u16,
+        duration: Duration,
+    ) -> io::Result<()> {
+        self.check_owner(effect)?;
+        self.upload_rumble(effect.id, strong, weak, duration)?;
+        self.play_effect(effect.id, true)
+    }
+
+    /// Stops a rumble effect and removes it from the device.
+    pub fn stop_rumble(&self, effect: FfEffectId<'_>) -> io::Result<()> {
+        self.check_owner(&effect)?;
+        let id = effect.id;
+        mem::forget(effect);
+        let stopped = self.play_effect(id, false);
+        self.remove_effect(id)?;
+        stopped
+    }
+
+    fn check_owner(&self, effect: &FfEffectId<'_>) -> io::Result<()> {
+        if ptr::eq(effect.joystick, self) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "effect was uploaded to a different joystick",
+            ))
+        }
+    }
+
+    fn upload_rumble(
+        &self,
+        id: i16,
+        strong: u16,
+        weak: u16,
+        duration: Duration,
+    ) -> io::Result<i16> {
+        if !self.supports_rumble() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "joystick does not support FF_RUMBLE",
+            ));
+        }
+        // SAFETY: ff_effect is plain data, for which all zeroes is a valid value.
+        let mut effect: libc::ff_effect = unsafe { mem::zeroed() };
+        effect.type_ = EV_FF::FF_RUMBLE as u16;
+        effect.id = id;
+        effect.replay.length = u16::try_from(duration.as_millis()).unwrap_or(u16::MAX);
+        let rumble = libc::ff_rumble_effect {
+            strong_magnitude: strong,
+            weak_magnitude: weak,
+        };
+        // SAFETY: `u` is the effect parameter union, which is large and aligned enough to
+        // hold an ff_rumble_effect at its start.
+        unsafe { ptr::write(effect.u.as_mut_ptr().cast(), rumble) };
+        // SAFETY: the pointer comes from a mutable reference, so the kernel may write the
+        // assigned id back into it.
+        unsafe { raw::evioc_send_ff(self.device.file().as_raw_fd(), &raw mut effect) }?;
+        Ok(effect.id)
+    }
+
+    fn play_effect(&self, id: i16, play: bool) -> io::Result<()> {
+        let event = InputEvent::new(
+            &TimeVal::new(0, 0),
+            &EventCode::EV_FF(EV_FF::FF_RUMBLE),
+            i32::from(play),
+        )
+        .as_raw();
+        let event = libc::input_event {
+            code: id as u16,
+            ..event
+        };
+        // SAFETY: input_event is plain data without padding, so it can be viewed as bytes.
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                (&raw const event).cast::<u8>(),
+                mem::size_of::<libc::input_event>(),
+            )
+        };
+        self.device.file().write_all(bytes)
+    }
+
+    fn remove_effect(&self, id: i16) -> io::Result<()> {
+        // SAFETY: EVIOCRMFF takes the effect id by value.
+        unsafe { raw::evioc_remove_ff(self.device.file().as_raw_fd(), id as u16 as _) }?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_rumble() {
+        // The uinput harness has no force feedback, so this needs a real rumble pad.
+        let joystick = crate::joystick::tests::find_a_joystick_with(Joystick::supports_rumble);
+        let effect = joystick
+            .rumble(0x8000, 0x4000, Duration::from_millis(200))
+            .expect("Rumble effect can be uploaded");
+        joystick
+            .replace_rumble(&effect, 0x4000, 0x8000, Duration::from_millis(200))
+            .expect("Rumble effect can be replaced");
+        joystick.stop_rumble(effect).expect("Rumble can be stopped");
+    }
+}
@@ -0,0 +1,181 @@
+use std::{io, mem};
+
+use evdev_rs::{
+    InputEvent,
+    enums::{EV_SYN, EventCode},
+};
+
+use super::{Joystick, JoystickEvents};
+
+/// The events of one report, i.e. everything up to (excluding) a `SYN_REPORT`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Frame {
+    pub events: Vec<InputEvent>,
+    /// Events were dropped by the kernel before this frame completed, so it is partial.
+    /// The device state (e.g. [`Joystick::abs_info`]) has been resynced by the time it is
+    /// yielded.
+    pub resync: bool,
+}
+
+pub struct JoystickFrames<'a> {
+    events: JoystickEvents<'a>,
+    buffer: Vec<InputEvent>,
+    // An event read after a resync, which starts the frame following the flushed one.
+    pending: Option<InputEvent>,
+}
+
+impl Joystick {
+    /// Groups events into [`Frame`]s, one per `SYN_REPORT`, so a report's axis and button
+    /// changes can be applied together.
+    pub fn frames(&self) -> JoystickFrames<'_> {
+        JoystickFrames {
+            events: self.events(),
+            buffer: Vec::new(),
+            pending: None,
+        }
+    }
+}
+
+impl Iterator for JoystickFrames<'_> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let event = match self.pending.take() {
+                Some(event) => event,
+                None => match self.events.next()? {
+                    Ok(event) => event,
+                    Err(e) => return Some(Err(e)),
+                },
+            };
+            if self.events.take_dropped() {
+                self.pending = Some(event);
+                return Some(Ok(Frame {
+                    events: mem::take(&mut self.buffer),
+                    resync: true,
+                }));
+            }
+            if event.event_code == EventCode::EV_SYN(EV_SYN::SYN_REPORT) {
+                return Some(Ok(Frame {
+                    events: mem::take(&mut self.buffer),
+                    resync: false,
+                }));
+            }
+            self.buffer.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use evdev_rs::{TimeVal, enums::EV_ABS};
+
+    use super::*;
+
+    fn abs(axis: EV_ABS, value: i32) -> InputEvent {
+        InputEvent::new(&TimeVal::new(0, 0), &EventCode::EV_ABS(axis), value)
+    }
+
+    fn codes(frame: &Frame) -> Vec<(EventCode, i32)> {
+        frame
+            .events
+            .iter()
+            .map(|event| (event.event_code, event.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_frames() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        device
+            .emit(&[abs(EV_ABS::ABS_X, 100), abs(EV_ABS::ABS_Y, -100)])
+            .expect("Events can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        device
+            .emit(&[abs(EV_ABS::ABS_RZ, 200)])
+            .expect("Event can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        let mut frames = joystick.frames();
+        let first = frames
+            .next()
+            .expect("Frame is read")
+            .expect("Frame is read");
+        assert!(!first.resync);
+        assert_eq!(
+            codes(&first),
+            [
+                (EventCode::EV_ABS(EV_ABS::ABS_X), 100),
+                (EventCode::EV_ABS(EV_ABS::ABS_Y), -100)
+            ]
+        );
+        let second = frames
+            .next()
+            .expect("Frame is read")
+            .expect("Frame is read");
+        assert!(!second.resync);
+        assert_eq!(codes(&second), [(EventCode::EV_ABS(EV_ABS::ABS_RZ), 200)]);
+    }
+
+    #[test]
+    fn test_frames_resync() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        // Far more reports than the kernel buffers for a reader, so it drops some.
+        const REPORTS: i32 = 1000;
+        for i in 0..REPORTS {
+            let value = if i % 2 == 0 { -100 } else { 100 };
+            device
+                .emit(&[abs(EV_ABS::ABS_X, value)])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+        }
+        let resynced = joystick
+            .frames()
+            .take(REPORTS as usize)
+            .map(|frame| frame.expect("Frame is read"))
+            .any(|frame| frame.resync);
+        assert!(resynced, "Overflowing the buffer is reported as a resync");
+        let abs_info = joystick
+            .abs_info_for(EV_ABS::ABS_X)
+            .expect("Harness has ABS_X");
+        assert_eq!(abs_info.value, 100);
+    }
+
+    #[test]
+    fn test_frames_wait() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let emitter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            device
+                .emit(&[abs(EV_ABS::ABS_X, 100)])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+            device
+        });
+        let started = Instant::now();
+        let cpu_started = crate::joystick::tests::thread_cpu_time();
+        let frame = joystick
+            .frames()
+            .next()
+            .expect("Frame is read")
+            .expect("Frame is read");
+        let cpu_time = crate::joystick::tests::thread_cpu_time() - cpu_started;
+        assert_eq!(codes(&frame), [(EventCode::EV_ABS(EV_ABS::ABS_X), 100)]);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(
+            cpu_time < Duration::from_millis(100),
+            "Waiting took {cpu_time:?} of CPU time"
+        );
+        drop(emitter.join().expect("Emitter doesn't panic"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::BTreeMap, io};
+
+use evdev_rs::enums::EV_ABS;
+
+use super::{Joystick, JoystickAbsInfo, JoystickEvent, TypedJoystickEvents};
+
+impl JoystickAbsInfo {
+    /// The normalized value, or `None` if the raw value moved less than `fuzz` from
+    /// `previous_raw`, the last value that was reported. A value that didn't move at all is
+    /// dropped even when `fuzz` is 0.
+    pub fn filtered(&self, previous_raw: i32) -> Option<i16> {
+        exceeds(self.value, previous_raw, self.fuzz).then(|| self.normalized_value())
+    }
+}
+
+fn exceeds(value: i32, previous: i32, threshold: i32) -> bool {
+    (i64::from(value) - i64::from(previous)).abs() >= i64::from(threshold.max(1))
+}
+
+/// Suppresses axis jitter by dropping changes smaller than each axis' `fuzz`, tracking the last
+/// reported value of every axis. The kernel applies a similar filter of its own, this leaves
+/// the threshold up to the application by way of the axis' `fuzz`.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzFilter {
+    last: BTreeMap<EV_ABS, i32>,
+    thresholds: BTreeMap<EV_ABS, i32>,
+}
+
+impl FuzzFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `threshold` in raw units for `axis` in place of its `fuzz`, e.g. to smooth a noisy
+    /// stick more aggressively.
+    pub fn set_threshold(&mut self, axis: EV_ABS, threshold: i32) {
+        self.thresholds.insert(axis, threshold);
+    }
+
+    /// The normalized value of `abs_info` if it moved far enough from the last value reported
+    /// for `axis`, which it then becomes. The first value of each axis always passes.
+    pub fn filter(&mut self, axis: EV_ABS, abs_info: &JoystickAbsInfo) -> Option<i16> {
+        let threshold = self.thresholds.get(&axis).copied().unwrap_or(abs_info.fuzz);
+        if let Some(&previous) = self.last.get(&axis)
+            && !exceeds(abs_info.value, previous, threshold)
+        {
+            return None;
+        }
+        self.last.insert(axis, abs_info.value);
+        Some(abs_info.normalized_value())
+    }
+
+    /// Forgets the last reported values, so every axis passes again.
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
+
+/// Typed events with axis jitter dropped by a [`FuzzFilter`], see
+/// [`Joystick::fuzz_filtered_events`].
+pub struct FuzzFilteredEvents<'a> {
+    joystick: &'a Joystick,
+    events: TypedJoystickEvents<'a>,
+    filter: FuzzFilter,
+}
+
+impl FuzzFilteredEvents<'_> {
+    /// Overrides the threshold of one axis, see [`FuzzFilter::set_threshold`].
+    pub fn with_threshold(mut self, axis: EV_ABS, threshold: i32) -> Self {
+        self.filter.set_threshold(axis, threshold);
+        self
+    }
+}
+
+impl Joystick {
+    /// Like [`Joystick::typed_events`], dropping axis events whose value moved less than the
+    /// axis' `fuzz` from the last value passed on for that axis.
+    pub fn fuzz_filtered_events(&self) -> FuzzFilteredEvents<'_> {
+        FuzzFilteredEvents {
+            joystick: self,
+            events: self.typed_events(),
+            filter: FuzzFilter::new(),
+        }
+    }
+}
+
+impl Iterator for FuzzFilteredEvents<'_> {
+    type Item = io::Result<JoystickEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let event = match self.events.next()? {
+                Ok(event) => event,
+                Err(e) => return Some(Err(e)),
+            };
+            if let JoystickEvent::AxisMoved { axis, value, .. } = event
+                && let Some(mut abs_info) = self.joystick.abs_info_for(axis)
+            {
+                abs_info.value = value;
+                if self.filter.filter(axis, &abs_info).is_none() {
+                    continue;
+                }
+            }
+            return Some(Ok(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev_rs::AbsInfo;
+
+    use super::*;
+
+    fn abs_info(value: i32, fuzz: i32) -> JoystickAbsInfo {
+        JoystickAbsInfo(AbsInfo {
+            value,
+            minimum: 0,
+            maximum: 1023,
+            fuzz,
+            flat: 0,
+            resolution: 0,
+        })
+    }
+
+    #[test]
+    fn test_filtered() {
+        assert_eq!(abs_info(100, 4).filtered(98), None);
+        assert_eq!(abs_info(100, 4).filtered(97), None);
+        // A change of exactly `fuzz` is not less than it, so passes.
+        assert!(abs_info(100, 4).filtered(96).is_some());
+        assert!(abs_info(100, 4).filtered(104).is_some());
+        assert!(abs_info(100, 4).filtered(95).is_some());
+        assert_eq!(abs_info(100, 0).filtered(100), None);
+        assert!(abs_info(100, 0).filtered(99).is_some());
+    }
+
+    #[test]
+    fn test_fuzz_filter() {
+        let mut filter = FuzzFilter::new();
+        assert!(filter.filter(EV_ABS::ABS_X, &abs_info(500, 8)).is_some());
+        assert_eq!(filter.filter(EV_ABS::ABS_X, &abs_info(505, 8)), None);
+        // Small steps don't add up, since only reported values are remembered.
+        assert_eq!(filter.filter(EV_ABS::ABS_X, &abs_info(507, 8)), None);
+        assert!(filter.filter(EV_ABS::ABS_X, &abs_info(508, 8)).is_some());
+        assert!(filter.filter(EV_ABS::ABS_Y, &abs_info(508, 8)).is_some());
+        filter.reset();
+        assert!(filter.filter(EV_ABS::ABS_X, &abs_info(508, 8)).is_some());
+    }
+
+    #[test]
+    fn test_threshold_override() {
+        let mut filter = FuzzFilter::new();
+        filter.set_threshold(EV_ABS::ABS_X, 32);
+        assert!(filter.filter(EV_ABS::ABS_X, &abs_info(500, 0)).is_some());
+        assert_eq!(filter.filter(EV_ABS::ABS_X, &abs_info(531, 0)), None);
+        assert!(filter.filter(EV_ABS::ABS_X, &abs_info(532, 0)).is_some());
+        // Other axes keep using their own fuzz.
+        assert!(filter.filter(EV_ABS::ABS_Y, &abs_info(500, 0)).is_some());
+        assert!(filter.filter(EV_ABS::ABS_Y, &abs_info(501, 0)).is_some());
+    }
+}
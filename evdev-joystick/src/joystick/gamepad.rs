@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+
+use evdev_rs::{
+    DeviceWrapper,
+    enums::{EV_ABS, EV_KEY, EventCode, EventType},
+};
+
+use super::{HatDirection, Joystick, NormalizeOptions};
+
+/// An axis of an XInput-style gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CanonicalAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl CanonicalAxis {
+    /// Triggers rest at one end of their range, so they normalize to `0..=i16::MAX`.
+    pub fn is_trigger(self) -> bool {
+        matches!(
+            self,
+            CanonicalAxis::LeftTrigger | CanonicalAxis::RightTrigger
+        )
+    }
+}
+
+/// A button of an XInput-style gamepad, named by position: `A` is the bottom face button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CanonicalButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftShoulder,
+    RightShoulder,
+    Back,
+    Start,
+    Guide,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Where a canonical axis reads its value from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisSource {
+    Abs {
+        axis: EV_ABS,
+        invert: bool,
+    },
+    /// One half of an axis, e.g. a trigger sharing an axis with the other trigger. Reads
+    /// `0..=i16::MAX` as the axis moves from its center towards the given end.
+    AbsHalf {
+        axis: EV_ABS,
+        positive: bool,
+    },
+    /// A digital button standing in for an axis, e.g. a trigger without analog travel.
+    Key(u32),
+}
+
+/// Where a canonical button reads its state from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonSource {
+    Key(u32),
+    /// Pressed while the hat points in `direction` (one of up, down, left or right),
+    /// including the diagonals next to it.
+    Hat {
+        hat: u8,
+        direction: HatDirection,
+    },
+    /// Pressed while the axis is past the middle of the given half of its range.
+    Abs {
+        axis: EV_ABS,
+        positive: bool,
+    },
+}
+
+/// Maps a device's axes and buttons onto the canonical gamepad layout.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GamepadLayout {
+    axes: BTreeMap<CanonicalAxis, AxisSource>,
+    buttons: BTreeMap<CanonicalButton, ButtonSource>,
+}
+
+impl GamepadLayout {
+    pub fn axis(&self, axis: CanonicalAxis) -> Option<AxisSource> {
+        self.axes.get(&axis).copied()
+    }
+
+    pub fn button(&self, button: CanonicalButton) -> Option<ButtonSource> {
+        self.buttons.get(&button).copied()
+    }
+
+    pub fn map_axis(&mut self, axis: CanonicalAxis, source: AxisSource) -> &mut Self {
+        self.axes.insert(axis, source);
+        self
+    }
+
+    pub fn map_button(&mut self, button: CanonicalButton, source: ButtonSource) -> &mut Self {
+        self.buttons.insert(button, source);
+        self
+    }
+
+    /// Guesses the layout from the codes the device reports, following the kernel's gamepad
+    /// conventions (`Documentation/input/gamepad.rst`). Codes the device lacks are left
+    /// unmapped.
+    pub fn from_capabilities(joystick: &Joystick) -> GamepadLayout {
+        let has_abs = |axis: EV_ABS| joystick.abs_axis.contains(&axis);
+        let has_key = |key: EV_KEY| joystick.buttons.contains_key(&(key as u32));
+        let abs = |axis| AxisSource::Abs {
+            axis,
+            invert: false,
+        };
+
+        let mut layout = GamepadLayout::default();
+        // Without ABS_RX/ABS_RY the right stick usually sits on ABS_Z/ABS_RZ, pushing the
+        // triggers onto the driving axes.
+        let (right, triggers) = if has_abs(EV_ABS::ABS_RX) && has_abs(EV_ABS::ABS_RY) {
+            (
+                [EV_ABS::ABS_RX, EV_ABS::ABS_RY],
+                [EV_ABS::ABS_Z, EV_ABS::ABS_RZ],
+            )
+        } else {
+            (
+                [EV_ABS::ABS_Z, EV_ABS::ABS_RZ],
+                [EV_ABS::ABS_BRAKE, EV_ABS::ABS_GAS],
+            )
+        };
+        let axes = [
+            (CanonicalAxis::LeftStickX, EV_ABS::ABS_X),
+            (CanonicalAxis::LeftStickY, EV_ABS::ABS_Y),
+            (CanonicalAxis::RightStickX, right[0]),
+            (CanonicalAxis::RightStickY, right[1]),
+            (CanonicalAxis::LeftTrigger, triggers[0]),
+            (CanonicalAxis::RightTrigger, triggers[1]),
+        ];
+        for (canonical, axis) in axes {
+            if has_abs(axis) {
+                layout.map_axis(canonical, abs(axis));
+            }
+        }
+        for (canonical, key) in [
+            (CanonicalAxis::LeftTrigger, EV_KEY::BTN_TL2),
+            (CanonicalAxis::RightTrigger, EV_KEY::BTN_TR2),
+        ] {
+            if layout.axis(canonical).is_none() && has_key(key) {
+                layout.map_axis(canonical, AxisSource::Key(key as u32));
+            }
+        }
+
+        // BTN_NORTH is the kernel's BTN_X and BTN_WEST its BTN_Y, despite their positions.
+        let buttons = [
+            (CanonicalButton::A, EV_KEY::BTN_SOUTH),
+            (CanonicalButton::B, EV_KEY::BTN_EAST),
+            (CanonicalButton::X, EV_KEY::BTN_NORTH),
+            (CanonicalButton::Y, EV_KEY::BTN_WEST),
+            (CanonicalButton::LeftShoulder, EV_KEY::BTN_TL),
+            (CanonicalButton::RightShoulder, EV_KEY::BTN_TR),
+            (CanonicalButton::Back, EV_KEY::BTN_SELECT),
+            (CanonicalButton::Start, EV_KEY::BTN_START),
+            (CanonicalButton::Guide, EV_KEY::BTN_MODE),
+            (CanonicalButton::LeftStick, EV_KEY::BTN_THUMBL),
+            (CanonicalButton::RightStick, EV_KEY::BTN_THUMBR),
+            (CanonicalButton::DPadUp, EV_KEY::BTN_DPAD_UP),
+            (CanonicalButton::DPadDown, EV_KEY::BTN_DPAD_DOWN),
+            (CanonicalButton::DPadLeft, EV_KEY::BTN_DPAD_LEFT),
+            (CanonicalButton::DPadRight, EV_KEY::BTN_DPAD_RIGHT),
+        ];
+        for (canonical, key) in buttons {
+            if has_key(key) {
+                layout.map_button(canonical, ButtonSource::Key(key as u32));
+            }
+        }
+        if has_abs(EV_ABS::ABS_HAT0X) && has_abs(EV_ABS::ABS_HAT0Y) {
+            for (canonical, direction) in [
+                (CanonicalButton::DPadUp, HatDirection::Up),
+                (CanonicalButton::DPadDown, HatDirection::Down),
+                (CanonicalButton::DPadLeft, HatDirection::Left),
+                (CanonicalButton::DPadRight, HatDirection::Right),
+            ] {
+                layout
+                    .buttons
+                    .entry(canonical)
+                    .or_insert(ButtonSource::Hat { hat: 0, direction });
+            }
+        }
+        layout
+    }
+}
+
+/// A joystick read through a [`GamepadLayout`].
+pub struct Gamepad<'a> {
+    joystick: &'a Joystick,
+    layout: GamepadLayout,
+}
+
+impl<'a> Gamepad<'a> {
+    pub fn new(joystick: &'a Joystick, layout: GamepadLayout) -> Self {
+        Gamepad { joystick, layout }
+    }
+
+    pub fn layout(&self) -> &GamepadLayout {
+        &self.layout
+    }
+
+    /// The normalized value of an axis: `i16::MIN..=i16::MAX` for sticks, with negative
+    /// pointing left or up, and `0..=i16::MAX` for triggers.
+    pub fn axis(&self, axis: CanonicalAxis) -> Option<i16> {
+        match self.layout.axis(axis)? {
+            AxisSource::Abs { axis: abs, invert } => {
+                let abs_info = self.joystick.abs_info_for(abs)?;
+                Some(abs_info.normalized_with(NormalizeOptions {
+                    invert,
+                    bipolar: !axis.is_trigger(),
+                    ..Default::default()
+                }))
+            }
+            AxisSource::AbsHalf {
+                axis: abs,
+                positive,
+            } => {
+                let abs_info = self.joystick.abs_info_for(abs)?;
+                let value = abs_info.normalized_with(NormalizeOptions::default());
+                Some(if positive {
+                    value.max(0)
+                } else {
+                    value.saturating_neg().max(0)
+                })
+            }
+            AxisSource::Key(code) => Some(if self.key_pressed(code)? { i16::MAX } else { 0 }),
+        }
+    }
+
+    pub fn button(&self, button: CanonicalButton) -> Option<bool> {
+        match self.layout.button(button)? {
+            ButtonSource::Key(code) => self.key_pressed(code),
+            ButtonSource::Hat { hat, direction } => {
+                let (x, y) = self.joystick.hat_direction(hat)?.to_axes();
+                let (want_x, want_y) = direction.to_axes();
+                Some((want_x != 0 && x == want_x) || (want_y != 0 && y == want_y))
+            }
+            ButtonSource::Abs { axis, positive } => {
+                let abs_info = self.joystick.abs_info_for(axis)?;
+                let value = abs_info.normalized_with(NormalizeOptions::default());
+                Some(if positive {
+                    value > i16::MAX / 2
+                } else {
+                    value < i16::MIN / 2
+                })
+            }
+        }
+    }
+
+    fn key_pressed(&self, code: u32) -> Option<bool> {
+        let value = self.joystick.device.event_value(&EventCode::EV_UNK {
+            event_type: EventType::EV_KEY as u32,
+            event_code: code,
+        })?;
+        Some(value != 0)
+    }
+}
+
+impl Joystick {
+    /// Reads the device as a gamepad, with the layout set by [`Joystick::apply_sdl_mapping`]
+    /// or otherwise guessed by [`GamepadLayout::from_capabilities`]. `None` if a guessed
+    /// layout has neither a left stick nor an `A` button, as the device is then unlikely to be
+    /// a gamepad at all.
+    pub fn gamepad(&self) -> Option<Gamepad<'_>> {
+        if let Some(layout) = &self.gamepad_layout {
+            return Some(Gamepad::new(self, layout.clone()));
+        }
+        let layout = GamepadLayout::from_capabilities(self);
+        let has_left_stick = layout.axis(CanonicalAxis::LeftStickX).is_some();
+        let has_a = layout.button(CanonicalButton::A).is_some();
+        (has_left_stick || has_a).then(|| Gamepad::new(self, layout))
+    }
+
+    /// Like [`Joystick::gamepad`], using the layout from `overrides` if it has one for the
+    /// device's vendor and product id.
+    pub fn gamepad_with_overrides(
+        &self,
+        overrides: &BTreeMap<(u16, u16), GamepadLayout>,
+    ) -> Option<Gamepad<'_>> {
+        match overrides.get(&(self.vendor_id(), self.product_id())) {
+            Some(layout) => Some(Gamepad::new(self, layout.clone())),
+            None => self.gamepad(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev_rs::{InputEvent, TimeVal};
+
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let mut layout = GamepadLayout::default();
+        layout
+            .map_axis(
+                CanonicalAxis::LeftStickX,
+                AxisSource::Abs {
+                    axis: EV_ABS::ABS_X,
+                    invert: false,
+                },
+            )
+            .map_button(
+                CanonicalButton::A,
+                ButtonSource::Key(EV_KEY::BTN_SOUTH as u32),
+            );
+        assert_eq!(
+            layout.axis(CanonicalAxis::LeftStickX),
+            Some(AxisSource::Abs {
+                axis: EV_ABS::ABS_X,
+                invert: false
+            })
+        );
+        assert_eq!(
+            layout.button(CanonicalButton::A),
+            Some(ButtonSource::Key(EV_KEY::BTN_SOUTH as u32))
+        );
+        assert_eq!(layout.button(CanonicalButton::B), None);
+        assert!(CanonicalAxis::RightTrigger.is_trigger());
+        assert!(!CanonicalAxis::RightStickY.is_trigger());
+    }
+
+    #[test]
+    fn test_gamepad() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let gamepad = joystick.gamepad().expect("Harness has a left stick");
+        let layout = gamepad.layout();
+        let abs = |axis| {
+            Some(AxisSource::Abs {
+                axis,
+                invert: false,
+            })
+        };
+        assert_eq!(layout.axis(CanonicalAxis::LeftStickX), abs(EV_ABS::ABS_X));
+        assert_eq!(layout.axis(CanonicalAxis::LeftStickY), abs(EV_ABS::ABS_Y));
+        // Without ABS_RX/ABS_RY, ABS_RZ is taken for the right stick.
+        assert_eq!(layout.axis(CanonicalAxis::RightStickY), abs(EV_ABS::ABS_RZ));
+        assert_eq!(layout.axis(CanonicalAxis::LeftTrigger), None);
+        assert_eq!(layout.button(CanonicalButton::A), None);
+        device
+            .emit(&[InputEvent::new(
+                &TimeVal::new(0, 0),
+                &EventCode::EV_ABS(EV_ABS::ABS_X),
+                511,
+            )])
+            .expect("Event can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        joystick.drain_events().for_each(drop);
+        assert_eq!(gamepad.axis(CanonicalAxis::LeftStickX), Some(i16::MAX));
+    }
+}
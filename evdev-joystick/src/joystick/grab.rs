@@ -0,0 +1,39 @@
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+};
+
+use super::Joystick;
+
+/// Exclusive grab of a [`Joystick`], released when dropped.
+#[derive(Debug)]
+pub struct JoystickGrab<'a>(&'a mut Joystick);
+
+impl<'a> JoystickGrab<'a> {
+    pub(crate) fn new(joystick: &'a mut Joystick) -> io::Result<Self> {
+        joystick.grab()?;
+        Ok(JoystickGrab(joystick))
+    }
+}
+
+impl Drop for JoystickGrab<'_> {
+    fn drop(&mut self) {
+        // Ungrabbing a device we grabbed ourselves can only fail if the device is gone,
+        // in which case there is nothing left to release.
+        let _ = self.0.ungrab();
+    }
+}
+
+impl Deref for JoystickGrab<'_> {
+    type Target = Joystick;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl DerefMut for JoystickGrab<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
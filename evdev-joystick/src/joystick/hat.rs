@@ -0,0 +1,203 @@
+use std::io;
+
+use evdev_rs::{
+    DeviceWrapper,
+    enums::{EV_ABS, EV_KEY, EventCode, int_to_ev_abs},
+};
+
+use super::Joystick;
+
+/// Direction of a hat switch (D-pad), combining its X and Y axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HatDirection {
+    Centered,
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl HatDirection {
+    /// Combines hat axis values, where negative `y` points up as reported by evdev.
+    pub fn from_axes(x: i32, y: i32) -> HatDirection {
+        match (x.signum(), y.signum()) {
+            (0, 0) => HatDirection::Centered,
+            (0, -1) => HatDirection::Up,
+            (1, -1) => HatDirection::UpRight,
+            (1, 0) => HatDirection::Right,
+            (1, 1) => HatDirection::DownRight,
+            (0, 1) => HatDirection::Down,
+            (-1, 1) => HatDirection::DownLeft,
+            (-1, 0) => HatDirection::Left,
+            (-1, -1) => HatDirection::UpLeft,
+            _ => unreachable!("signum is always -1, 0 or 1"),
+        }
+    }
+
+    /// The inverse of [`HatDirection::from_axes`], with each axis `-1`, `0` or `1`.
+    pub fn to_axes(self) -> (i32, i32) {
+        match self {
+            HatDirection::Centered => (0, 0),
+            HatDirection::Up => (0, -1),
+            HatDirection::UpRight => (1, -1),
+            HatDirection::Right => (1, 0),
+            HatDirection::DownRight => (1, 1),
+            HatDirection::Down => (0, 1),
+            HatDirection::DownLeft => (-1, 1),
+            HatDirection::Left => (-1, 0),
+            HatDirection::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Combines the four buttons of a D-pad reporting `BTN_DPAD_*` keys instead of hat axes.
+    /// Opposite buttons held together cancel out.
+    pub fn from_buttons(up: bool, down: bool, left: bool, right: bool) -> HatDirection {
+        let axis = |negative: bool, positive: bool| i32::from(positive) - i32::from(negative);
+        HatDirection::from_axes(axis(left, right), axis(up, down))
+    }
+}
+
+/// The D-pad buttons in the argument order of [`HatDirection::from_buttons`].
+const DPAD_BUTTONS: [EV_KEY; 4] = [
+    EV_KEY::BTN_DPAD_UP,
+    EV_KEY::BTN_DPAD_DOWN,
+    EV_KEY::BTN_DPAD_LEFT,
+    EV_KEY::BTN_DPAD_RIGHT,
+];
+
+/// The hat an `ABS_HAT*` axis belongs to, or `None` for other axes.
+pub fn hat_of_axis(axis: EV_ABS) -> Option<u8> {
+    let offset = (axis as u32).checked_sub(EV_ABS::ABS_HAT0X as u32)?;
+    (axis as u32 <= EV_ABS::ABS_HAT3Y as u32).then_some((offset / 2) as u8)
+}
+
+pub(crate) fn is_dpad_button(key: EV_KEY) -> bool {
+    DPAD_BUTTONS.contains(&key)
+}
+
+impl Joystick {
+    /// The current direction of hat `hat` (`ABS_HAT{hat}X`/`ABS_HAT{hat}Y`), or `None` if the
+    /// device lacks either axis.
+    pub fn hat_direction(&self, hat: u8) -> Option<HatDirection> {
+        let (x, y) = hat_axes(hat)?;
+        let x = self.device.event_value(&EventCode::EV_ABS(x))?;
+        let y = self.device.event_value(&EventCode::EV_ABS(y))?;
+        Some(HatDirection::from_axes(x, y))
+    }
+
+    /// Like [`Joystick::hat_direction`], falling back to the `BTN_DPAD_*` buttons for hat 0 on
+    /// devices which report their D-pad as buttons, read with `EVIOCGKEY`. Fails with
+    /// [`io::ErrorKind::NotFound`] if the device has no such hat.
+    pub fn hat_state(&self, hat: u8) -> io::Result<HatDirection> {
+        if let Some(direction) = self.hat_direction(hat) {
+            return Ok(direction);
+        }
+        if hat == 0 && self.has_dpad_buttons() {
+            let states = self.button_states()?;
+            let [up, down, left, right] = DPAD_BUTTONS.map(|key| {
+                self.get_button_index(&EventCode::EV_KEY(key))
+                    .and_then(|index| states.get(&index).copied())
+                    .unwrap_or(false)
+            });
+            return Ok(HatDirection::from_buttons(up, down, left, right));
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("joystick has no hat {hat}"),
+        ))
+    }
+
+    /// The direction of the `BTN_DPAD_*` buttons as libevdev last saw them.
+    pub(crate) fn dpad_direction(&self) -> HatDirection {
+        let [up, down, left, right] = DPAD_BUTTONS.map(|key| {
+            self.device
+                .event_value(&EventCode::EV_KEY(key))
+                .is_some_and(|value| value != 0)
+        });
+        HatDirection::from_buttons(up, down, left, right)
+    }
+
+    pub(crate) fn is_dpad_index(&self, index: u32) -> bool {
+        DPAD_BUTTONS
+            .iter()
+            .any(|&key| self.buttons.get(&(key as u32)) == Some(&index))
+    }
+
+    fn has_dpad_buttons(&self) -> bool {
+        DPAD_BUTTONS
+            .iter()
+            .any(|&key| self.buttons.contains_key(&(key as u32)))
+    }
+}
+
+fn hat_axes(hat: u8) -> Option<(EV_ABS, EV_ABS)> {
+    if hat > 3 {
+        return None;
+    }
+    let x = EV_ABS::ABS_HAT0X as u32 + 2 * u32::from(hat);
+    Some((int_to_ev_abs(x)?, int_to_ev_abs(x + 1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_axes() {
+        let table = [
+            ((0, 0), HatDirection::Centered),
+            ((0, -1), HatDirection::Up),
+            ((1, -1), HatDirection::UpRight),
+            ((1, 0), HatDirection::Right),
+            ((1, 1), HatDirection::DownRight),
+            ((0, 1), HatDirection::Down),
+            ((-1, 1), HatDirection::DownLeft),
+            ((-1, 0), HatDirection::Left),
+            ((-1, -1), HatDirection::UpLeft),
+        ];
+        for ((x, y), direction) in table {
+            assert_eq!(HatDirection::from_axes(x, y), direction, "({x}, {y})");
+            assert_eq!(direction.to_axes(), (x, y));
+        }
+    }
+
+    #[test]
+    fn test_from_buttons() {
+        assert_eq!(
+            HatDirection::from_buttons(false, false, false, false),
+            HatDirection::Centered
+        );
+        assert_eq!(
+            HatDirection::from_buttons(true, false, false, true),
+            HatDirection::UpRight
+        );
+        assert_eq!(
+            HatDirection::from_buttons(false, true, true, false),
+            HatDirection::DownLeft
+        );
+        assert_eq!(
+            HatDirection::from_buttons(true, true, true, false),
+            HatDirection::Left
+        );
+    }
+
+    #[test]
+    fn test_hat_of_axis() {
+        assert_eq!(hat_of_axis(EV_ABS::ABS_HAT0X), Some(0));
+        assert_eq!(hat_of_axis(EV_ABS::ABS_HAT0Y), Some(0));
+        assert_eq!(hat_of_axis(EV_ABS::ABS_HAT3Y), Some(3));
+        assert_eq!(hat_of_axis(EV_ABS::ABS_X), None);
+        assert_eq!(hat_of_axis(EV_ABS::ABS_PRESSURE), None);
+    }
+
+    #[test]
+    fn test_hat_axes() {
+        assert_eq!(hat_axes(0), Some((EV_ABS::ABS_HAT0X, EV_ABS::ABS_HAT0Y)));
+        assert_eq!(hat_axes(3), Some((EV_ABS::ABS_HAT3X, EV_ABS::ABS_HAT3Y)));
+        assert_eq!(hat_axes(4), None);
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    io,
+    os::fd::{AsFd, BorrowedFd},
+    path::PathBuf,
+};
+
+use nix::{
+    errno::Errno,
+    poll::{PollFd, PollFlags, PollTimeout, poll},
+};
+use udev::{EventType, MonitorBuilder, MonitorSocket};
+
+/// A joystick being plugged in or unplugged, with the path of its event node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HotplugEvent {
+    Connected(PathBuf),
+    Disconnected(PathBuf),
+}
+
+/// Watches udev for joysticks being plugged in and unplugged. Unlike
+/// [`JoystickMonitor`](super::JoystickMonitor) the events come from udev itself, so a
+/// [`HotplugEvent::Connected`] device already has its permissions applied by the time it is
+/// reported, ready for [`Joystick::new_from_path`](super::Joystick::new_from_path).
+///
+/// Only `/dev/input/event*` nodes udev tags with `ID_INPUT_JOYSTICK` are reported. Iterating
+/// blocks until the next event.
+pub struct HotplugMonitor {
+    socket: MonitorSocket,
+}
+
+impl HotplugMonitor {
+    pub fn new() -> io::Result<Self> {
+        let socket = MonitorBuilder::new()?.match_subsystem("input")?.listen()?;
+        Ok(HotplugMonitor { socket })
+    }
+
+    /// The next event udev already sent, or `None` instead of waiting if there is none.
+    pub fn try_next(&self) -> Option<HotplugEvent> {
+        self.socket.iter().find_map(|event| {
+            let is_joystick = event
+                .property_value("ID_INPUT_JOYSTICK")
+                .is_some_and(|value| value == "1");
+            let devnode = event.devnode()?;
+            let is_event_node = devnode.file_name()?.to_str()?.starts_with("event");
+            if !(is_joystick && is_event_node) {
+                return None;
+            }
+            match event.event_type() {
+                EventType::Add => Some(HotplugEvent::Connected(devnode.to_owned())),
+                EventType::Remove => Some(HotplugEvent::Disconnected(devnode.to_owned())),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl Iterator for HotplugMonitor {
+    type Item = io::Result<HotplugEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.try_next() {
+                return Some(Ok(event));
+            }
+            let mut fds = [PollFd::new(self.socket.as_fd(), PollFlags::POLLIN)];
+            match poll(&mut fds, PollTimeout::NONE) {
+                Ok(_) | Err(Errno::EINTR) => (),
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+impl AsFd for HotplugMonitor {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+/// Shared body of the hotplug streams: returns the next event, waiting on `poll_read_ready`
+/// whenever udev has sent nothing new.
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+fn poll_next_hotplug(
+    monitor: &HotplugMonitor,
+    mut poll_read_ready: impl FnMut() -> std::task::Poll<io::Result<()>>,
+) -> std::task::Poll<Option<io::Result<HotplugEvent>>> {
+    use std::task::Poll;
+
+    loop {
+        if let Some(event) = monitor.try_next() {
+            return Poll::Ready(Some(Ok(event)));
+        }
+        match poll_read_ready() {
+            Poll::Ready(Ok(())) => (),
+            Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_stream {
+    use std::{
+        io,
+        os::fd::{AsRawFd, RawFd},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures_core::Stream;
+    use tokio::io::{Interest, unix::AsyncFd};
+
+    use super::{HotplugEvent, HotplugMonitor, poll_next_hotplug};
+
+    /// Asynchronous stream of [`HotplugEvent`]s, driven by tokio's reactor.
+    pub struct HotplugStream<'a> {
+        monitor: &'a HotplugMonitor,
+        // Registered on first poll, since registering requires a running tokio runtime.
+        fd: Option<AsyncFd<RawFd>>,
+    }
+
+    impl HotplugMonitor {
+        /// Streams events as udev sends them. Must be polled from within a tokio runtime.
+        pub fn stream(&self) -> HotplugStream<'_> {
+            HotplugStream {
+                monitor: self,
+                fd: None,
+            }
+        }
+    }
+
+    impl Stream for HotplugStream<'_> {
+        type Item = io::Result<HotplugEvent>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let HotplugStream { monitor, fd } = self.get_mut();
+            poll_next_hotplug(monitor, || {
+                let fd = match fd {
+                    Some(fd) => fd,
+                    None => fd.insert(AsyncFd::with_interest(
+                        monitor.socket.as_raw_fd(),
+                        Interest::READABLE,
+                    )?),
+                };
+                match fd.poll_read_ready(cx) {
+                    Poll::Ready(Ok(mut guard)) => {
+                        guard.clear_ready();
+                        Poll::Ready(Ok(()))
+                    }
+                    Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                    Poll::Pending => Poll::Pending,
+                }
+            })
+        }
+    }
+}
+
+#[cfg(feature = "async-io")]
+mod async_io_stream {
+    use std::{
+        io,
+        os::fd::{AsFd, BorrowedFd},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use async_io::Async;
+    use futures_core::Stream;
+
+    use super::{HotplugEvent, HotplugMonitor, poll_next_hotplug};
+
+    /// Runtime-agnostic stream of [`HotplugEvent`]s, driven by `async-io`'s reactor.
+    pub struct AsyncIoHotplugStream<'a> {
+        monitor: &'a HotplugMonitor,
+        fd: Async<BorrowedFd<'a>>,
+    }
+
+    impl HotplugMonitor {
+        /// Streams events as udev sends them, on any runtime built on `async-io`.
+        pub fn async_io_stream(&self) -> io::Result<AsyncIoHotplugStream<'_>> {
+            Ok(AsyncIoHotplugStream {
+                monitor: self,
+                fd: Async::new(self.as_fd())?,
+            })
+        }
+    }
+
+    impl Stream for AsyncIoHotplugStream<'_> {
+        type Item = io::Result<HotplugEvent>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let AsyncIoHotplugStream { monitor, fd } = self.get_mut();
+            poll_next_hotplug(monitor, || fd.poll_readable(cx))
+        }
+    }
+}
+
+#[cfg(feature = "async-io")]
+pub use async_io_stream::AsyncIoHotplugStream;
+#[cfg(feature = "tokio")]
+pub use tokio_stream::HotplugStream;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_hotplug_monitor() {
+        let mut monitor = HotplugMonitor::new().expect("udev monitor can be created");
+        println!("Plug in or unplug a joystick");
+        let event = monitor
+            .next()
+            .expect("Monitor yields an event")
+            .expect("Event is read without error");
+        println!("{:?}", event);
+    }
+}
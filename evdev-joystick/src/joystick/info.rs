@@ -0,0 +1,184 @@
+use std::{fmt, io};
+
+use evdev_rs::DeviceWrapper;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Joystick, axis_name};
+use crate::raw;
+
+/// The identity of an input device, as reported by `EVIOCGID`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputId {
+    pub bustype: u16,
+    pub vendor: u16,
+    pub product: u16,
+    pub version: u16,
+}
+
+/// A summary of what a device is, suitable for showing in a device picker.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceInfo {
+    pub name: Option<String>,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub version: u16,
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:04x}:{:04x})",
+            self.name.as_deref().unwrap_or("(unnamed)"),
+            self.vendor_id,
+            self.product_id
+        )
+    }
+}
+
+/// Summarizes the device: its name and ids, how many buttons it has and the current info of
+/// each absolute axis, one per line.
+impl fmt::Display for Joystick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = self.input_id();
+        writeln!(f, "{}", self.info())?;
+        writeln!(
+            f,
+            "Input id: bus 0x{:04x}, vendor 0x{:04x}, product 0x{:04x}, version 0x{:04x}",
+            id.bustype, id.vendor, id.product, id.version
+        )?;
+        write!(f, "Buttons: {}", self.buttons.len())?;
+        for (axis, abs_info) in self.all_abs_info() {
+            write!(f, "\n{}: {}", axis_name(axis), abs_info)?;
+        }
+        Ok(())
+    }
+}
+
+impl Joystick {
+    /// The device's name, from `EVIOCGNAME`.
+    pub fn name(&self) -> Option<String> {
+        non_empty(self.device.name())
+    }
+
+    /// The device's physical location (e.g. `usb-0000:00:14.0-2/input0`), from `EVIOCGPHYS`.
+    pub fn phys(&self) -> Option<String> {
+        non_empty(self.device.phys())
+    }
+
+    /// The device's unique identifier (typically a serial number), from `EVIOCGUNIQ`. Unlike
+    /// the port-dependent by-id path this survives replugging, but many devices don't supply
+    /// one.
+    pub fn uniq(&self) -> Option<String> {
+        non_empty(self.device.uniq())
+    }
+
+    /// Reads the name from the kernel with `EVIOCGNAME`, rather than the copy libevdev read
+    /// when the device was opened.
+    #[cfg(test)]
+    pub(crate) fn read_name(&self) -> io::Result<Option<String>> {
+        raw::read_string(self.fd(), raw::evioc_get_name)
+    }
+
+    /// Reads the bus, vendor, product and version from the kernel with `EVIOCGID`, rather than
+    /// the copy [`Joystick::input_id`] returns.
+    #[cfg(test)]
+    pub(crate) fn read_input_id(&self) -> io::Result<InputId> {
+        // SAFETY: input_id is plain integers, for which all zeroes is valid.
+        let mut id: libc::input_id = unsafe { std::mem::zeroed() };
+        // SAFETY: EVIOCGID writes a single input_id.
+        unsafe { raw::evioc_get_id(self.fd(), &mut id) }?;
+        Ok(InputId {
+            bustype: id.bustype,
+            vendor: id.vendor,
+            product: id.product,
+            version: id.version,
+        })
+    }
+
+    /// The evdev protocol version of the driver, from `EVIOCGVERSION`. The raw value packs
+    /// major, minor and patch into its upper, middle and lower bytes, e.g. `0x010001`.
+    pub fn driver_version(&self) -> io::Result<i32> {
+        let mut version = 0;
+        // SAFETY: EVIOCGVERSION writes a single int.
+        unsafe { raw::evioc_get_version(self.fd(), &mut version) }?;
+        Ok(version)
+    }
+
+    pub fn vendor_id(&self) -> u16 {
+        self.device.vendor_id()
+    }
+
+    pub fn product_id(&self) -> u16 {
+        self.device.product_id()
+    }
+
+    pub fn version(&self) -> u16 {
+        self.device.version()
+    }
+
+    pub fn info(&self) -> DeviceInfo {
+        DeviceInfo {
+            name: self.name(),
+            vendor_id: self.vendor_id(),
+            product_id: self.product_id(),
+            version: self.version(),
+        }
+    }
+
+    pub fn input_id(&self) -> InputId {
+        InputId {
+            bustype: self.device.bustype(),
+            vendor: self.device.vendor_id(),
+            product: self.device.product_id(),
+            version: self.device.version(),
+        }
+    }
+
+    /// Opens the first joystick from [`Joystick::joysticks`] with the given vendor and product
+    /// id. Devices that can't be opened are skipped.
+    pub fn find_by_id(vendor: u16, product: u16) -> io::Result<Option<Joystick>> {
+        Ok(Joystick::joysticks()?.flatten().find(|joystick| {
+            let id = joystick.input_id();
+            id.vendor == vendor && id.product == product
+        }))
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.filter(|value| !value.is_empty()).map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_info_display() {
+        let info = DeviceInfo {
+            name: Some("Thrustmaster TWCS Throttle".to_owned()),
+            vendor_id: 0x044f,
+            product_id: 0xb687,
+            version: 0x0111,
+        };
+        assert_eq!(info.to_string(), "Thrustmaster TWCS Throttle (044f:b687)");
+        let info = DeviceInfo { name: None, ..info };
+        assert_eq!(info.to_string(), "(unnamed) (044f:b687)");
+    }
+
+    #[test]
+    fn test_read_from_kernel() {
+        let Some((_device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        assert_eq!(
+            joystick.read_name().expect("Name can be read"),
+            joystick.name()
+        );
+        let id = joystick.read_input_id().expect("Id can be read");
+        assert_eq!(id, joystick.input_id());
+        assert_eq!((id.vendor, id.product), (0x1234, 0x5678));
+    }
+}
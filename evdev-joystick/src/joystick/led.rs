@@ -0,0 +1,43 @@
+use std::io;
+
+use evdev_rs::{
+    LedState,
+    enums::{EV_LED, EventCode},
+};
+
+use super::Joystick;
+use crate::raw;
+
+impl Joystick {
+    pub fn leds(&self) -> impl Iterator<Item = EV_LED> {
+        self.leds.iter().copied()
+    }
+
+    /// Turns an LED on or off by writing an `EV_LED` event to the device.
+    pub fn set_led(&self, led: EV_LED, on: bool) -> io::Result<()> {
+        self.check_led(led)?;
+        let state = if on { LedState::On } else { LedState::Off };
+        self.device
+            .kernel_set_led_value(&EventCode::EV_LED(led), state)
+    }
+
+    /// Reads whether an LED is currently lit, from `EVIOCGLED`.
+    pub fn led_state(&self, led: EV_LED) -> io::Result<bool> {
+        self.check_led(led)?;
+        let mut bits = raw::bitmask(EV_LED::LED_MAX as usize);
+        // SAFETY: the buffer's length is passed along with it.
+        unsafe { raw::evioc_get_led(self.fd(), &mut bits) }?;
+        Ok(raw::test_bit(&bits, led as usize))
+    }
+
+    fn check_led(&self, led: EV_LED) -> io::Result<()> {
+        if self.leds.contains(&led) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("joystick does not have {}", EventCode::EV_LED(led)),
+            ))
+        }
+    }
+}
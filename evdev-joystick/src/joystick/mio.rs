@@ -0,0 +1,62 @@
+use std::io;
+
+use mio::{Interest, Registry, Token, event::Source, unix::SourceFd};
+
+use super::Joystick;
+
+/// Registers the device's fd, so a joystick can sit in a `mio::Poll` next to sockets. mio
+/// only offers edge-triggered readiness, so after each readable event read with
+/// [`Joystick::try_next_event`] or [`Joystick::drain_events`] until nothing is left, or the
+/// device won't be reported again.
+impl Source for Joystick {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.fd()).deregister(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mio::{Events, Poll};
+
+    use super::*;
+
+    #[test]
+    fn test_mio_source() {
+        let Some((device, mut joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let mut poll = Poll::new().expect("Poll can be created");
+        poll.registry()
+            .register(&mut joystick, Token(0), Interest::READABLE)
+            .expect("Joystick can be registered");
+        device.syn().expect("SYN_REPORT can be emitted");
+        let mut events = Events::with_capacity(4);
+        poll.poll(&mut events, Some(Duration::from_secs(1)))
+            .expect("Poll succeeds");
+        assert!(events.iter().any(|event| event.token() == Token(0)));
+        assert!(joystick.drain_events().count() > 0);
+        poll.registry()
+            .deregister(&mut joystick)
+            .expect("Joystick can be deregistered");
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io,
+    os::fd::{AsFd, BorrowedFd},
+    path::{Path, PathBuf},
+};
+
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+
+use super::{Joystick, enumerate::is_by_id_joystick};
+
+/// A joystick appearing in or disappearing from the watched directory.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MonitorEvent {
+    Added(PathBuf),
+    Removed(PathBuf),
+}
+
+/// Watches for joysticks being plugged in and unplugged, using inotify on
+/// `/dev/input/by-id/`. Only `*-event-joystick` entries are reported, the same ones
+/// [`Joystick::joysticks`] lists. Where there is no by-id directory, `/dev/input/` is
+/// watched instead, see [`JoystickMonitor::watch_event_nodes`].
+///
+/// Iterating blocks until the next change. A newly added device node may take a moment to
+/// become readable while udev applies its permissions.
+pub struct JoystickMonitor {
+    inotify: Inotify,
+    dir: PathBuf,
+    filter: Filter,
+    pending: VecDeque<MonitorEvent>,
+}
+
+/// How a [`JoystickMonitor`] tells joysticks apart from other entries.
+enum Filter {
+    /// By-id links, recognised by name.
+    ById,
+    /// `event*` nodes, recognised by opening them. Removed nodes can't be opened, so the
+    /// joysticks seen so far are kept to know which removals to report.
+    EventNodes(HashSet<PathBuf>),
+}
+
+impl JoystickMonitor {
+    /// Watches `/dev/input/by-id/`, or `/dev/input/` if there is no by-id directory yet, as
+    /// on a machine where nothing with a by-id link has been plugged in since boot.
+    pub fn new() -> io::Result<Self> {
+        match Self::watch("/dev/input/by-id/") {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::watch_event_nodes("/dev/input/"),
+            result => result,
+        }
+    }
+
+    /// Watches `dir` instead of `/dev/input/by-id/`.
+    pub fn watch(dir: impl AsRef<Path>) -> io::Result<Self> {
+        Self::with_filter(dir.as_ref(), Filter::ById)
+    }
+
+    /// Watches the `event*` nodes of `dir`, a directory laid out like `/dev/input/`. Nodes
+    /// are opened to check [`Joystick::looks_like_joystick`], so only joysticks that can be
+    /// opened are reported. One that can't be opened yet is reported once udev has made it
+    /// readable.
+    pub fn watch_event_nodes(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let known = event_nodes(dir)?
+            .filter(|path| is_openable_joystick(path))
+            .collect();
+        Self::with_filter(dir, Filter::EventNodes(known))
+    }
+
+    fn with_filter(dir: &Path, filter: Filter) -> io::Result<Self> {
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC)?;
+        let mut flags = AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_TO
+            | AddWatchFlags::IN_MOVED_FROM;
+        if let Filter::EventNodes(_) = filter {
+            // udev changes the node's owner and mode after creating it.
+            flags |= AddWatchFlags::IN_ATTRIB;
+        }
+        inotify.add_watch(dir, flags)?;
+        Ok(JoystickMonitor {
+            inotify,
+            dir: dir.to_owned(),
+            filter,
+            pending: VecDeque::new(),
+        })
+    }
+
+    fn read_events(&mut self) -> io::Result<()> {
+        for event in self.inotify.read_events()? {
+            let Some(name) = event.name else { continue };
+            let path = self.dir.join(name);
+            let added = event.mask.intersects(
+                AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO | AddWatchFlags::IN_ATTRIB,
+            );
+            match &mut self.filter {
+                Filter::ById if !is_by_id_joystick(&path) => continue,
+                Filter::ById => (),
+                Filter::EventNodes(_) if !is_event_node(&path) => continue,
+                Filter::EventNodes(known) if added => {
+                    if known.contains(&path) || !is_openable_joystick(&path) {
+                        continue;
+                    }
+                    known.insert(path.clone());
+                }
+                Filter::EventNodes(known) => {
+                    if !known.remove(&path) {
+                        continue;
+                    }
+                }
+            }
+            if added {
+                self.pending.push_back(MonitorEvent::Added(path));
+            } else {
+                self.pending.push_back(MonitorEvent::Removed(path));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_event_node(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str()?.strip_prefix("event"))
+        .is_some_and(|number| number.parse::<u32>().is_ok())
+}
+
+fn event_nodes(dir: &Path) -> io::Result<impl Iterator<Item = PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| is_event_node(path)))
+}
+
+fn is_openable_joystick(path: &Path) -> bool {
+    Joystick::new_from_path(path).is_ok_and(|joystick| joystick.looks_like_joystick())
+}
+
+impl Iterator for JoystickMonitor {
+    type Item = io::Result<MonitorEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if let Err(e) = self.read_events() {
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+impl AsFd for JoystickMonitor {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inotify.as_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_monitor() {
+        let dir =
+            std::env::temp_dir().join(format!("evdev-joystick-monitor-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("Fixture directory can be created");
+        let mut monitor = JoystickMonitor::watch(&dir).expect("Directory can be watched");
+        let joystick = dir.join("usb-pad-event-joystick");
+        fs::write(dir.join("usb-kbd-event-kbd"), "").expect("Fixture file can be created");
+        fs::write(&joystick, "").expect("Fixture file can be created");
+        fs::remove_file(&joystick).expect("Fixture file can be removed");
+        assert_eq!(
+            monitor.next().unwrap().unwrap(),
+            MonitorEvent::Added(joystick.clone())
+        );
+        assert_eq!(
+            monitor.next().unwrap().unwrap(),
+            MonitorEvent::Removed(joystick)
+        );
+        fs::remove_dir_all(dir).expect("Fixture directory can be removed");
+    }
+
+    #[test]
+    fn test_monitor_event_nodes() {
+        if !crate::uinput::uinput_writable() {
+            return;
+        }
+        let mut monitor =
+            JoystickMonitor::watch_event_nodes("/dev/input/").expect("/dev/input can be watched");
+        let (device, joystick) =
+            crate::uinput::test_joystick().expect("Virtual joystick can be created");
+        let devnode = joystick
+            .path()
+            .expect("Virtual joystick has a path")
+            .to_owned();
+        drop(joystick);
+        // Other tests create virtual joysticks of their own at the same time.
+        let mut next = || {
+            monitor
+                .find(|event| match event {
+                    Ok(MonitorEvent::Added(path) | MonitorEvent::Removed(path)) => *path == devnode,
+                    Err(_) => true,
+                })
+                .expect("Monitor doesn't end")
+                .expect("Events can be read")
+        };
+        assert_eq!(next(), MonitorEvent::Added(devnode.clone()));
+        drop(device);
+        assert_eq!(next(), MonitorEvent::Removed(devnode));
+    }
+
+    #[test]
+    fn test_is_event_node() {
+        assert!(is_event_node(Path::new("/dev/input/event12")));
+        assert!(!is_event_node(Path::new("/dev/input/js0")));
+        assert!(!is_event_node(Path::new("/dev/input/eventful")));
+        assert!(!is_event_node(Path::new("/dev/input/by-id")));
+    }
+}
@@ -0,0 +1,33 @@
+use evdev_rs::enums::{EV_ABS, EventCode, EventType};
+
+use crate::raw;
+
+/// The kernel's name for an axis, e.g. `ABS_RZ`, for use in config files and profiles.
+pub fn axis_name(axis: EV_ABS) -> &'static str {
+    raw::event_code_name(EventType::EV_ABS as u32, axis as u32).unwrap_or("ABS_UNKNOWN")
+}
+
+/// The axis named by [`axis_name`], or `None` if `name` isn't an axis.
+pub fn axis_from_name(name: &str) -> Option<EV_ABS> {
+    match EventCode::from_str(&EventType::EV_ABS, name)? {
+        EventCode::EV_ABS(axis) => Some(axis),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_names() {
+        assert_eq!(axis_name(EV_ABS::ABS_X), "ABS_X");
+        assert_eq!(axis_name(EV_ABS::ABS_RZ), "ABS_RZ");
+        for axis in [EV_ABS::ABS_X, EV_ABS::ABS_RZ, EV_ABS::ABS_HAT0Y] {
+            assert_eq!(axis_name(axis), EventCode::EV_ABS(axis).to_string());
+            assert_eq!(axis_from_name(axis_name(axis)), Some(axis));
+        }
+        assert_eq!(axis_from_name("BTN_SOUTH"), None);
+        assert_eq!(axis_from_name("not an axis"), None);
+    }
+}
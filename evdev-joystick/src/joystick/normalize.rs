@@ -0,0 +1,354 @@
+use evdev_rs::AbsInfo;
+
+use super::{JoystickAbsInfo, OutOfRange};
+
+/// Options for [`JoystickAbsInfo::normalized_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// Maps `minimum` to the top of the output range instead of the bottom.
+    pub invert: bool,
+    /// Deadzone in normalized units, replacing the axis' own `flat` value when set.
+    pub deadzone: Option<i16>,
+    /// Maps onto `i16::MIN..=i16::MAX` when set, or `0..=i16::MAX` otherwise
+    /// (useful for triggers and throttles).
+    pub bipolar: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        NormalizeOptions {
+            invert: false,
+            deadzone: None,
+            bipolar: true,
+        }
+    }
+}
+
+/// Shaping applied to a normalized `[-1.0, 1.0]` axis value, preserving its sign.
+#[derive(Debug, Clone, Copy)]
+pub enum ResponseCurve {
+    Linear,
+    Quadratic,
+    Cubic,
+    /// Applied to the magnitude of the value, which is in `[0.0, 1.0]`.
+    Custom(fn(f32) -> f32),
+}
+
+impl ResponseCurve {
+    pub fn apply(self, value: f32) -> f32 {
+        let magnitude = value.abs();
+        let shaped = match self {
+            ResponseCurve::Linear => magnitude,
+            ResponseCurve::Quadratic => magnitude * magnitude,
+            ResponseCurve::Cubic => magnitude * magnitude * magnitude,
+            ResponseCurve::Custom(curve) => curve(magnitude),
+        };
+        shaped.copysign(value)
+    }
+}
+
+impl JoystickAbsInfo {
+    /// Normalizes to `[-1.0, 1.0]`, applies the deadzone, then shapes the result with `curve`.
+    pub fn normalized_with_curve(&self, curve: ResponseCurve) -> f32 {
+        let value = (f32::from(self.normalized_value()) / f32::from(i16::MAX)).max(-1.0);
+        if value == 0.0 {
+            return 0.0;
+        }
+        curve.apply(value)
+    }
+
+    /// Like [`JoystickAbsInfo::normalized_value`], but fails instead of clamping a raw value
+    /// outside `minimum..=maximum`, to catch devices that exceed their declared range.
+    pub fn normalized_checked(&self) -> Result<i16, OutOfRange> {
+        if (self.minimum..=self.maximum).contains(&self.value) {
+            Ok(self.normalized_value())
+        } else {
+            Err(OutOfRange {
+                value: self.value,
+                minimum: self.minimum,
+                maximum: self.maximum,
+            })
+        }
+    }
+
+    pub fn normalized_value_inverted(&self) -> i16 {
+        self.normalized_with(NormalizeOptions {
+            invert: true,
+            ..Default::default()
+        })
+    }
+
+    /// Like [`JoystickAbsInfo::normalized_value`], ignoring the axis' `flat` in favour of
+    /// `deadzone`, in normalized units.
+    pub fn normalized_value_with_deadzone(&self, deadzone: i16) -> i16 {
+        self.normalized_with(NormalizeOptions {
+            deadzone: Some(deadzone),
+            ..Default::default()
+        })
+    }
+
+    pub fn normalized_with(&self, options: NormalizeOptions) -> i16 {
+        let &JoystickAbsInfo(AbsInfo {
+            value,
+            minimum,
+            maximum,
+            flat,
+            ..
+        }) = self;
+
+        let (minimum, maximum) = (i64::from(minimum), i64::from(maximum));
+        let value = i64::from(value).max(minimum).min(maximum);
+        // Inverting reflects the raw value within its range, so the deadzone still
+        // lands on the same (now mirrored) region around the center.
+        let value = if options.invert {
+            minimum + maximum - value
+        } else {
+            value
+        };
+        let range_size = maximum - minimum;
+        // A degenerate range carries no position, so report the axis as centred.
+        if range_size <= 0 {
+            return 0;
+        }
+        let norm_value = if options.bipolar {
+            const I16_RANGE: i64 = u16::MAX as i64;
+            div_round((value - minimum) * I16_RANGE, range_size) + i64::from(i16::MIN)
+        } else {
+            div_round((value - minimum) * i64::from(i16::MAX), range_size)
+        };
+        let norm_value = norm_value.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        match options.deadzone {
+            Some(deadzone) => apply_deadzone(norm_value, deadzone),
+            None => apply_flatness(norm_value, flat),
+        }
+    }
+}
+
+/// Divides rounding to the nearest integer, with halves rounded away from zero. Truncating
+/// would bias every axis towards its minimum.
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    let half = denominator / 2;
+    if (numerator < 0) == (denominator < 0) {
+        (numerator + half) / denominator
+    } else {
+        (numerator - half) / denominator
+    }
+}
+
+fn apply_flatness(value: i16, flat: i32) -> i16 {
+    if (value as i32) >= (-flat).div_euclid(2) && (value as i32) <= flat.div_euclid(2) {
+        0
+    } else {
+        value
+    }
+}
+
+fn apply_deadzone(value: i16, deadzone: i16) -> i16 {
+    if value.unsigned_abs() <= deadzone.unsigned_abs() {
+        0
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abs_info(value: i32, minimum: i32, maximum: i32, flat: i32) -> JoystickAbsInfo {
+        JoystickAbsInfo(AbsInfo {
+            value,
+            minimum,
+            maximum,
+            fuzz: 0,
+            flat,
+            resolution: 0,
+        })
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(abs_info(0, 0, 255, 0).normalized_value(), i16::MIN);
+        assert_eq!(abs_info(255, 0, 255, 0).normalized_value(), i16::MAX);
+        assert_eq!(abs_info(-100, -100, 100, 0).normalized_value(), i16::MIN);
+        assert_eq!(abs_info(100, -100, 100, 0).normalized_value(), i16::MAX);
+    }
+
+    #[test]
+    fn test_normalized_checked() {
+        assert_eq!(
+            abs_info(1000, 0, 1000, 0).normalized_checked(),
+            Ok(i16::MAX)
+        );
+        assert_eq!(abs_info(0, 0, 1000, 0).normalized_checked(), Ok(i16::MIN));
+        let err = abs_info(1050, 0, 1000, 0)
+            .normalized_checked()
+            .expect_err("Value is above maximum");
+        assert_eq!(
+            err,
+            OutOfRange {
+                value: 1050,
+                minimum: 0,
+                maximum: 1000
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "axis value 1050 is outside its range 0..=1000"
+        );
+        assert!(abs_info(-1, 0, 1000, 0).normalized_checked().is_err());
+        // The infallible version keeps clamping.
+        assert_eq!(abs_info(1050, 0, 1000, 0).normalized_value(), i16::MAX);
+    }
+
+    #[test]
+    fn test_degenerate_range() {
+        assert_eq!(abs_info(5, 5, 5, 0).normalized_value(), 0);
+        assert_eq!(abs_info(7, 5, 5, 0).normalized_value_inverted(), 0);
+        assert_eq!(abs_info(0, 10, -10, 0).normalized_value(), 0);
+        let unipolar = NormalizeOptions {
+            bipolar: false,
+            ..Default::default()
+        };
+        assert_eq!(abs_info(5, 5, 5, 0).normalized_with(unipolar), 0);
+    }
+
+    #[test]
+    fn test_midpoint_rounding() {
+        // (minimum, maximum, value, truncated, rounded): truncation used to pull centered
+        // sticks below zero whenever the exact result was fractional.
+        let cases = [
+            (-100, 100, 0, -1, 0),
+            (0, 254, 127, -1, 0),
+            (0, 1023, 511, -33, -33),
+            (0, 1023, 512, 31, 32),
+            (0, 255, 127, -129, -129),
+            (0, 255, 128, 128, 128),
+        ];
+        for (minimum, maximum, value, truncated, rounded) in cases {
+            let truncating =
+                i64::from(value - minimum) * 65535 / i64::from(maximum - minimum) - 32768;
+            assert_eq!(truncating, truncated);
+            assert_eq!(
+                abs_info(value, minimum, maximum, 0).normalized_value(),
+                rounded,
+                "{minimum}..={maximum} at {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_div_round() {
+        assert_eq!(div_round(5, 2), 3);
+        assert_eq!(div_round(-5, 2), -3);
+        assert_eq!(div_round(4, 3), 1);
+        assert_eq!(div_round(-4, 3), -1);
+        assert_eq!(div_round(5, -2), -3);
+    }
+
+    #[test]
+    fn test_inverted() {
+        assert_eq!(abs_info(0, 0, 255, 0).normalized_value_inverted(), i16::MAX);
+        assert_eq!(
+            abs_info(255, 0, 255, 0).normalized_value_inverted(),
+            i16::MIN
+        );
+        for value in 0..=255 {
+            let info = abs_info(value, 0, 255, 0);
+            let mirrored = abs_info(255 - value, 0, 255, 0);
+            assert_eq!(
+                info.normalized_value_inverted(),
+                mirrored.normalized_value()
+            );
+        }
+    }
+
+    #[test]
+    fn test_inverted_flat() {
+        let info = abs_info(128, 0, 255, 1000);
+        assert_eq!(info.normalized_value(), 0);
+        assert_eq!(info.normalized_value_inverted(), 0);
+        for (value, minimum, maximum) in [(0, -100, 100), (127, 0, 254), (511, 0, 1023)] {
+            let info = abs_info(value, minimum, maximum, 1000);
+            assert_eq!(info.normalized_value_inverted(), 0, "{minimum}..={maximum}");
+        }
+    }
+
+    #[test]
+    fn test_deadzone() {
+        let options = NormalizeOptions {
+            deadzone: Some(4096),
+            ..Default::default()
+        };
+        assert_eq!(abs_info(140, 0, 255, 0).normalized_with(options), 0);
+        assert_ne!(abs_info(200, 0, 255, 0).normalized_with(options), 0);
+        let inverted = NormalizeOptions {
+            invert: true,
+            ..options
+        };
+        assert_eq!(abs_info(115, 0, 255, 0).normalized_with(inverted), 0);
+        assert_eq!(
+            abs_info(140, 0, 255, 0).normalized_value_with_deadzone(4096),
+            0
+        );
+        // The axis' own flat is ignored in favour of the given deadzone.
+        let flat = abs_info(140, 0, 255, 8192);
+        assert_eq!(flat.normalized_value(), 0);
+        assert_ne!(flat.normalized_value_with_deadzone(0), 0);
+    }
+
+    #[test]
+    fn test_linear_curve() {
+        for value in 0..=255 {
+            let info = abs_info(value, 0, 255, 0);
+            let expected = (f32::from(info.normalized_value()) / f32::from(i16::MAX)).max(-1.0);
+            assert_eq!(info.normalized_with_curve(ResponseCurve::Linear), expected);
+        }
+    }
+
+    #[test]
+    fn test_curves_monotonic() {
+        let curves = [
+            ResponseCurve::Linear,
+            ResponseCurve::Quadratic,
+            ResponseCurve::Cubic,
+            ResponseCurve::Custom(f32::sqrt),
+        ];
+        for curve in curves {
+            let values: Vec<f32> = (0..=255)
+                .map(|value| abs_info(value, 0, 255, 0).normalized_with_curve(curve))
+                .collect();
+            assert!(
+                values.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{curve:?}"
+            );
+            assert_eq!(values[0], -1.0);
+            assert_eq!(values[255], 1.0);
+        }
+    }
+
+    #[test]
+    fn test_curve_keeps_flat() {
+        let info = abs_info(128, 0, 255, 1000);
+        assert_eq!(info.normalized_with_curve(ResponseCurve::Quadratic), 0.0);
+        assert_eq!(ResponseCurve::Quadratic.apply(-0.5), -0.25);
+    }
+
+    #[test]
+    fn test_unipolar() {
+        let options = NormalizeOptions {
+            bipolar: false,
+            ..Default::default()
+        };
+        assert_eq!(abs_info(0, 0, 1023, 0).normalized_with(options), 0);
+        assert_eq!(
+            abs_info(1023, 0, 1023, 0).normalized_with(options),
+            i16::MAX
+        );
+        let inverted = NormalizeOptions {
+            invert: true,
+            ..options
+        };
+        assert_eq!(abs_info(0, 0, 1023, 0).normalized_with(inverted), i16::MAX);
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+
+use evdev_rs::enums::{self, InputProp};
+use libc::c_ulong;
+
+use super::Joystick;
+use crate::raw;
+
+impl Joystick {
+    /// The device's `INPUT_PROP_*` properties, from `EVIOCGPROP`. These tell e.g. an
+    /// accelerometer or pointing stick apart from a joystick with the same axes.
+    pub fn properties(&self) -> io::Result<Vec<InputProp>> {
+        let bits = self.property_bits()?;
+        Ok((0..InputProp::INPUT_PROP_MAX as u32)
+            .filter(|&prop| raw::test_bit(&bits, prop as usize))
+            .filter_map(enums::int_to_input_prop)
+            .collect())
+    }
+
+    /// The raw property bitmask from `EVIOCGPROP`, including bits without an [`InputProp`].
+    pub fn property_bits(&self) -> io::Result<Vec<c_ulong>> {
+        let mut bits = raw::bitmask(InputProp::INPUT_PROP_MAX as usize);
+        // SAFETY: the buffer's length is passed along with it.
+        unsafe { raw::evioc_get_prop(self.fd(), &mut bits) }?;
+        Ok(bits)
+    }
+}
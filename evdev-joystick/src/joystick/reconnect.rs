@@ -0,0 +1,195 @@
+use std::{io, thread, time::Duration};
+
+#[cfg(feature = "udev")]
+use super::{HotplugEvent, HotplugMonitor};
+use super::{InputId, Joystick, JoystickEvent, is_disconnected};
+
+/// A joystick that is reopened when it is unplugged and plugged back in, found again by its
+/// name and input id since its `/dev/input/eventN` node usually changes. Reopening gives a
+/// fresh device, so grabs, clocks and calibration written to the old one have to be redone.
+pub struct ReconnectingJoystick {
+    joystick: Joystick,
+    name: Option<String>,
+    id: InputId,
+    retry_interval: Duration,
+    #[cfg(feature = "udev")]
+    hotplug: Option<HotplugMonitor>,
+}
+
+impl ReconnectingJoystick {
+    pub fn new(joystick: Joystick) -> Self {
+        ReconnectingJoystick {
+            name: joystick.name(),
+            id: joystick.input_id(),
+            joystick,
+            retry_interval: Duration::from_secs(1),
+            #[cfg(feature = "udev")]
+            hotplug: None,
+        }
+    }
+
+    /// How long to wait between looking for the device again, one second by default.
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Waits for udev to report a joystick before looking for the device again, instead of
+    /// retrying every interval.
+    #[cfg(feature = "udev")]
+    pub fn with_hotplug_monitor(mut self, monitor: HotplugMonitor) -> Self {
+        self.hotplug = Some(monitor);
+        self
+    }
+
+    /// The currently open device, replaced on every reconnect.
+    pub fn joystick(&self) -> &Joystick {
+        &self.joystick
+    }
+
+    /// Whether `joystick` has the name and input id of the device this was created with.
+    pub fn matches(&self, joystick: &Joystick) -> bool {
+        joystick.name() == self.name && joystick.input_id() == self.id
+    }
+
+    /// Looks for the device among [`Joystick::joysticks`] once, reopening it if found.
+    /// Returns whether it was.
+    pub fn try_reconnect(&mut self) -> io::Result<bool> {
+        let joysticks = match Joystick::joysticks() {
+            Ok(joysticks) => joysticks,
+            // The last device went away along with /dev/input/by-id.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        match joysticks.flatten().find(|joystick| self.matches(joystick)) {
+            Some(joystick) => {
+                self.joystick = joystick;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Waits until the device is back and reopens it.
+    pub fn reconnect(&mut self) -> io::Result<()> {
+        while !self.try_reconnect()? {
+            self.wait()?;
+        }
+        Ok(())
+    }
+
+    fn wait(&mut self) -> io::Result<()> {
+        #[cfg(feature = "udev")]
+        if let Some(monitor) = &mut self.hotplug {
+            // Any joystick will do, whether it is this one is checked on the next try.
+            for event in monitor {
+                if let HotplugEvent::Connected(_) = event? {
+                    return Ok(());
+                }
+            }
+        }
+        thread::sleep(self.retry_interval);
+        Ok(())
+    }
+
+    /// Like [`Joystick::typed_events`], reconnecting whenever the device is disconnected.
+    /// Other errors are yielded as they are.
+    pub fn typed_events(&mut self) -> ReconnectingEvents<'_> {
+        ReconnectingEvents { joystick: self }
+    }
+}
+
+/// Typed events that resume once a disconnected device returns, see
+/// [`ReconnectingJoystick::typed_events`].
+pub struct ReconnectingEvents<'a> {
+    joystick: &'a mut ReconnectingJoystick,
+}
+
+impl Iterator for ReconnectingEvents<'_> {
+    type Item = io::Result<JoystickEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.joystick.joystick.typed_events().next()? {
+                Err(e) if is_disconnected(&e) => {
+                    if let Err(e) = self.joystick.reconnect() {
+                        return Some(Err(e));
+                    }
+                }
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use evdev_rs::{
+        InputEvent, TimeVal,
+        enums::{EV_KEY, EventCode},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_reconnecting_joystick() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let mut reconnecting = ReconnectingJoystick::new(joystick);
+        assert!(reconnecting.matches(reconnecting.joystick()));
+        drop(device);
+        let err = reconnecting
+            .joystick()
+            .typed_events()
+            .next()
+            .expect("Disconnect is reported")
+            .expect_err("Device is gone");
+        assert!(is_disconnected(&err));
+        assert!(
+            !reconnecting
+                .try_reconnect()
+                .expect("Joysticks can be listed")
+        );
+    }
+
+    #[test]
+    fn test_reconnecting_events_wait() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let thumb = EventCode::EV_KEY(EV_KEY::BTN_THUMB);
+        let index = joystick
+            .get_button_index(&thumb)
+            .expect("Harness has BTN_THUMB");
+        let mut reconnecting = ReconnectingJoystick::new(joystick);
+        let emitter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            device
+                .emit(&[InputEvent::new(&TimeVal::new(0, 0), &thumb, 1)])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+            device
+        });
+        let started = Instant::now();
+        let cpu_started = crate::joystick::tests::thread_cpu_time();
+        let event = reconnecting
+            .typed_events()
+            .next()
+            .expect("Event is read")
+            .expect("Event is read");
+        let cpu_time = crate::joystick::tests::thread_cpu_time() - cpu_started;
+        assert!(
+            matches!(event, JoystickEvent::ButtonChanged { index: i, pressed: true, .. } if i == index),
+            "{event:?}"
+        );
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(
+            cpu_time < Duration::from_millis(100),
+            "Waiting took {cpu_time:?} of CPU time"
+        );
+        drop(emitter.join().expect("Emitter doesn't panic"));
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    io::{self, Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use evdev_rs::{InputEvent, TimeVal};
+
+use super::{Joystick, is_disconnected};
+
+const MAGIC: &[u8; 4] = b"EVJR";
+const VERSION: u8 = 1;
+const HEADER: [u8; 8] = [MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], VERSION, 0, 0, 0];
+const RECORD_LEN: usize = 16;
+
+/// An event read back from a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordedEvent {
+    /// Time since the first event of the trace.
+    pub offset: Duration,
+    pub event_type: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl RecordedEvent {
+    /// The event as an [`InputEvent`], with `offset` as its time.
+    pub fn to_input_event(&self) -> InputEvent {
+        InputEvent::from_raw(&libc::input_event {
+            time: libc::timeval {
+                tv_sec: self.offset.as_secs() as _,
+                tv_usec: self.offset.subsec_micros() as _,
+            },
+            type_: self.event_type,
+            code: self.code,
+            value: self.value,
+        })
+    }
+
+    fn to_bytes(self) -> [u8; RECORD_LEN] {
+        let mut bytes = [0; RECORD_LEN];
+        let micros = u64::try_from(self.offset.as_micros()).unwrap_or(u64::MAX);
+        bytes[0..8].copy_from_slice(&micros.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.event_type.to_le_bytes());
+        bytes[10..12].copy_from_slice(&self.code.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.value.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: [u8; RECORD_LEN]) -> Self {
+        RecordedEvent {
+            offset: Duration::from_micros(u64::from_le_bytes(
+                bytes[0..8].try_into().expect("8 bytes"),
+            )),
+            event_type: u16::from_le_bytes(bytes[8..10].try_into().expect("2 bytes")),
+            code: u16::from_le_bytes(bytes[10..12].try_into().expect("2 bytes")),
+            value: i32::from_le_bytes(bytes[12..16].try_into().expect("4 bytes")),
+        }
+    }
+}
+
+/// Writes events to a trace, timing them relative to the first one.
+///
+/// A trace starts with an 8 byte header: the magic `EVJR`, a format version byte (currently
+/// `1`) and three zero bytes. Each event follows as a 16 byte record, all fields
+/// little-endian:
+///
+/// | bytes  | field                                                        |
+/// |--------|--------------------------------------------------------------|
+/// | 0..8   | `u64` microseconds since the first event of the trace        |
+/// | 8..10  | `u16` event type (`EV_*`)                                    |
+/// | 10..12 | `u16` event code                                             |
+/// | 12..16 | `i32` event value                                            |
+///
+/// The trace ends with the last complete record. `EV_SYN` events are recorded like any
+/// other, so frame boundaries are kept.
+pub struct EventRecorder<W: Write> {
+    writer: W,
+    start: Option<TimeVal>,
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Starts a trace by writing its header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&HEADER)?;
+        Ok(EventRecorder {
+            writer,
+            start: None,
+        })
+    }
+
+    pub fn write_event(&mut self, event: &InputEvent) -> io::Result<()> {
+        let start = *self.start.get_or_insert(event.time);
+        let micros =
+            (event.time.tv_sec - start.tv_sec) * 1_000_000 + (event.time.tv_usec - start.tv_usec);
+        let raw = event.as_raw();
+        let record = RecordedEvent {
+            // Clocks can step backwards (e.g. CLOCK_REALTIME), which would give a negative offset.
+            offset: Duration::from_micros(micros.max(0) as u64),
+            event_type: raw.type_,
+            code: raw.code,
+            value: raw.value,
+        };
+        self.writer.write_all(&record.to_bytes())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Iterator over the events of a trace.
+pub struct EventTrace<R: Read> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read> EventTrace<R> {
+    /// Reads the trace header, failing with [`io::ErrorKind::InvalidData`] if it isn't one.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; HEADER.len()];
+        reader.read_exact(&mut header)?;
+        if header[..4] != MAGIC[..] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an event trace",
+            ));
+        }
+        if header[4] != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported event trace version {}", header[4]),
+            ));
+        }
+        Ok(EventTrace {
+            reader,
+            done: false,
+        })
+    }
+}
+
+impl<R: Read> Iterator for EventTrace<R> {
+    type Item = io::Result<RecordedEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut bytes = [0; RECORD_LEN];
+        match self.reader.read_exact(&mut bytes) {
+            Ok(()) => Some(Ok(RecordedEvent::from_bytes(bytes))),
+            // A partial last record is what a recording cut short leaves behind.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Feeds the events of a trace to `sink`, waiting between them to reproduce their original
+/// timing.
+pub fn replay(reader: impl Read, sink: impl FnMut(InputEvent) -> io::Result<()>) -> io::Result<()> {
+    replay_events(EventTrace::new(reader)?, 1.0, sink)
+}
+
+/// Feeds `events` to `sink`, `speed` times faster than they were recorded.
+pub(crate) fn replay_events(
+    events: impl IntoIterator<Item = io::Result<RecordedEvent>>,
+    speed: f64,
+    mut sink: impl FnMut(InputEvent) -> io::Result<()>,
+) -> io::Result<()> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("replay speed must be positive, not {speed}"),
+        ));
+    }
+    let start = Instant::now();
+    for event in events {
+        let event = event?;
+        let due = start + event.offset.div_f64(speed);
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+        sink(event.to_input_event())?;
+    }
+    Ok(())
+}
+
+impl Joystick {
+    /// Records every event to `writer` in the format described at [`EventRecorder`], until
+    /// the device is disconnected. Other read and write errors end the recording with an
+    /// error; what was written up to that point is still a valid trace.
+    pub fn record(&self, writer: impl Write) -> io::Result<()> {
+        let mut recorder = EventRecorder::new(writer)?;
+        for event in self.events() {
+            match event {
+                Ok(event) => recorder.write_event(&event)?,
+                Err(e) if is_disconnected(&e) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        recorder.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev_rs::enums::{EV_ABS, EV_SYN, EventCode};
+
+    use super::*;
+
+    fn event(sec: i64, usec: i64, code: EventCode, value: i32) -> InputEvent {
+        InputEvent::new(&TimeVal::new(sec, usec), &code, value)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let events = [
+            event(100, 999_000, EventCode::EV_ABS(EV_ABS::ABS_X), -512),
+            event(100, 999_000, EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
+            event(101, 1_000, EventCode::EV_ABS(EV_ABS::ABS_X), 511),
+            event(101, 1_000, EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
+        ];
+        let mut recorder = EventRecorder::new(Vec::new()).expect("Vec accepts the header");
+        for event in &events {
+            recorder.write_event(event).expect("Vec accepts events");
+        }
+        let trace = recorder.into_inner();
+        assert_eq!(trace.len(), HEADER.len() + events.len() * RECORD_LEN);
+
+        let recorded = EventTrace::new(trace.as_slice())
+            .expect("Trace has a header")
+            .collect::<io::Result<Vec<_>>>()
+            .expect("Trace is readable");
+        assert_eq!(recorded[0].offset, Duration::ZERO);
+        assert_eq!(recorded[2].offset, Duration::from_millis(2));
+        assert_eq!(recorded[2].value, 511);
+        let replayed = recorded
+            .iter()
+            .map(RecordedEvent::to_input_event)
+            .collect::<Vec<_>>();
+        for (replayed, original) in replayed.iter().zip(&events) {
+            assert_eq!(replayed.event_code, original.event_code);
+            assert_eq!(replayed.value, original.value);
+        }
+        assert_eq!(replayed[3].time, TimeVal::new(0, 2_000));
+
+        // A truncated last record is dropped.
+        let truncated = &trace[..trace.len() - 3];
+        assert_eq!(EventTrace::new(truncated).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut recorder = EventRecorder::new(Vec::new()).unwrap();
+        recorder
+            .write_event(&event(5, 0, EventCode::EV_ABS(EV_ABS::ABS_Y), 7))
+            .unwrap();
+        recorder
+            .write_event(&event(5, 10_000, EventCode::EV_ABS(EV_ABS::ABS_Y), 8))
+            .unwrap();
+        let trace = recorder.into_inner();
+        let start = Instant::now();
+        let mut values = Vec::new();
+        replay(trace.as_slice(), |event| {
+            values.push(event.value);
+            Ok(())
+        })
+        .expect("Trace replays");
+        assert_eq!(values, [7, 8]);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_replay_speed() {
+        let events = [0, 40].map(|millis| {
+            Ok(RecordedEvent {
+                offset: Duration::from_millis(millis),
+                event_type: 0,
+                code: 0,
+                value: 0,
+            })
+        });
+        let start = Instant::now();
+        replay_events(events, 4.0, |_| Ok(())).expect("Events replay");
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(10), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(40), "{elapsed:?}");
+
+        let err = replay_events([], 0.0, |_| Ok(())).expect_err("Speed must be positive");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let err = EventTrace::new(&b"NOTATRACE"[..])
+            .err()
+            .expect("Header is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
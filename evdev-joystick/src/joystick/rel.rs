@@ -0,0 +1,67 @@
+use evdev_rs::enums::EV_REL;
+
+use super::Joystick;
+
+// Relative axes report deltas with no minimum or maximum, so the normalization of absolute
+// axes doesn't apply to them; their values are only scaled, or summed into a position.
+
+impl Joystick {
+    /// A relative delta multiplied by `scale`, e.g. to turn wheel clicks into lines. `None` if
+    /// the joystick has no such axis.
+    pub fn rel_scaled(&self, axis: EV_REL, raw: i32, scale: f32) -> Option<f32> {
+        self.rel_axis.contains(&axis).then_some(raw as f32 * scale)
+    }
+}
+
+/// Integrates the deltas of a relative axis into a virtual position clamped to
+/// `[-bound, bound]`, like a throttle driven by a scroll wheel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelAccumulator {
+    scale: f32,
+    bound: f32,
+    position: f32,
+}
+
+impl RelAccumulator {
+    /// Starts at 0, with every delta multiplied by `scale`.
+    pub fn new(scale: f32, bound: f32) -> Self {
+        RelAccumulator {
+            scale,
+            bound: bound.abs(),
+            position: 0.0,
+        }
+    }
+
+    /// Adds a raw delta and returns the new position. Deltas past a bound are dropped, so
+    /// moving back starts right away.
+    pub fn feed(&mut self, raw: i32) -> f32 {
+        self.position = (self.position + raw as f32 * self.scale).clamp(-self.bound, self.bound);
+        self.position
+    }
+
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    pub fn reset(&mut self) {
+        self.position = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rel_accumulator() {
+        let mut wheel = RelAccumulator::new(0.25, 1.0);
+        assert_eq!(wheel.feed(2), 0.5);
+        assert_eq!(wheel.feed(3), 1.0);
+        // Past the bound, so moving back counts from it.
+        assert_eq!(wheel.feed(-1), 0.75);
+        assert_eq!(wheel.feed(-20), -1.0);
+        assert_eq!(wheel.position(), -1.0);
+        wheel.reset();
+        assert_eq!(wheel.position(), 0.0);
+    }
+}
@@ -0,0 +1,453 @@
+use std::{fmt::Write, str::FromStr};
+
+use evdev_rs::enums::{EV_ABS, EV_KEY};
+use thiserror::Error;
+
+use super::{
+    AxisSource, ButtonSource, CanonicalAxis, CanonicalButton, GamepadLayout, HatDirection, InputId,
+    Joystick, hat_of_axis,
+};
+
+/// A malformed SDL mapping, or one referring to inputs the joystick lacks.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SdlMappingError {
+    #[error("mapping has no name")]
+    MissingName,
+    #[error("invalid GUID {0:?}, expected 32 hex digits")]
+    InvalidGuid(String),
+    #[error("malformed entry {0:?}, expected `name:input`")]
+    MalformedEntry(String),
+    #[error("invalid input {input:?} for `{target}`")]
+    InvalidInput { target: String, input: String },
+    #[error("`{target}` is mapped to {input:?}, which the joystick doesn't have")]
+    MissingInput { target: String, input: String },
+}
+
+/// The first joystick button, `BTN_JOYSTICK` in the kernel headers.
+const BTN_JOYSTICK: u32 = EV_KEY::BTN_TRIGGER as u32;
+
+/// An input as numbered by SDL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SdlInput {
+    Button(usize),
+    /// `half` is `Some(true)` for `+aN` and `Some(false)` for `-aN`.
+    Axis {
+        index: usize,
+        half: Option<bool>,
+        invert: bool,
+    },
+    Hat {
+        index: usize,
+        direction: HatDirection,
+    },
+}
+
+impl SdlInput {
+    fn parse(input: &str) -> Option<SdlInput> {
+        let (half, rest) = match input.as_bytes().first()? {
+            b'+' => (Some(true), &input[1..]),
+            b'-' => (Some(false), &input[1..]),
+            _ => (None, input),
+        };
+        if let Some(axis) = rest.strip_prefix('a') {
+            let (index, invert) = match axis.strip_suffix('~') {
+                Some(index) => (index, true),
+                None => (axis, false),
+            };
+            return Some(SdlInput::Axis {
+                index: index.parse().ok()?,
+                half,
+                invert,
+            });
+        }
+        if half.is_some() {
+            return None;
+        }
+        if let Some(button) = rest.strip_prefix('b') {
+            return Some(SdlInput::Button(button.parse().ok()?));
+        }
+        let (index, mask) = rest.strip_prefix('h')?.split_once('.')?;
+        let direction = match mask {
+            "1" => HatDirection::Up,
+            "2" => HatDirection::Right,
+            "4" => HatDirection::Down,
+            "8" => HatDirection::Left,
+            _ => return None,
+        };
+        Some(SdlInput::Hat {
+            index: index.parse().ok()?,
+            direction,
+        })
+    }
+}
+
+/// A line of SDL's `gamecontrollerdb.txt`, e.g.
+/// `030000005e0400008e02000014010000,Xbox 360 Controller,a:b0,b:b1,leftx:a0,…`.
+///
+/// Entries SDL knows but which have no [`CanonicalAxis`] or [`CanonicalButton`]
+/// (`platform`, `misc1`, paddles, …) are ignored, as are half-axis outputs such as
+/// `+leftx:b2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdlMapping {
+    pub guid: String,
+    pub name: String,
+    // Each input keeps the entry it was parsed from, for reporting errors.
+    axes: Vec<(CanonicalAxis, SdlInput, Entry)>,
+    buttons: Vec<(CanonicalButton, SdlInput, Entry)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    target: String,
+    input: String,
+}
+
+impl FromStr for SdlMapping {
+    type Err = SdlMappingError;
+
+    fn from_str(mapping: &str) -> Result<Self, Self::Err> {
+        let mut fields = mapping.trim().split(',');
+        let guid = fields.next().unwrap_or_default();
+        if guid.len() != 32 || !guid.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(SdlMappingError::InvalidGuid(guid.to_owned()));
+        }
+        let name = fields.next().ok_or(SdlMappingError::MissingName)?;
+
+        let mut axes = Vec::new();
+        let mut buttons = Vec::new();
+        for entry in fields.filter(|entry| !entry.is_empty()) {
+            let (target, input) = entry
+                .split_once(':')
+                .ok_or_else(|| SdlMappingError::MalformedEntry(entry.to_owned()))?;
+            let axis = sdl_axis(target);
+            let button = sdl_button(target);
+            if axis.is_none() && button.is_none() {
+                continue;
+            }
+            let parsed = SdlInput::parse(input).ok_or_else(|| SdlMappingError::InvalidInput {
+                target: target.to_owned(),
+                input: input.to_owned(),
+            })?;
+            let entry = Entry {
+                target: target.to_owned(),
+                input: input.to_owned(),
+            };
+            match (axis, button) {
+                (Some(axis), _) => axes.push((axis, parsed, entry)),
+                (_, Some(button)) => buttons.push((button, parsed, entry)),
+                (None, None) => unreachable!("unknown targets are skipped"),
+            }
+        }
+        Ok(SdlMapping {
+            guid: guid.to_ascii_lowercase(),
+            name: name.to_owned(),
+            axes,
+            buttons,
+        })
+    }
+}
+
+impl SdlMapping {
+    /// Resolves SDL's input numbering against a device with the given buttons and absolute
+    /// axes, in ascending code order.
+    fn layout(
+        &self,
+        buttons: &[u32],
+        abs_axes: &[EV_ABS],
+    ) -> Result<GamepadLayout, SdlMappingError> {
+        // SDL numbers joystick buttons before the miscellaneous ones below them, and numbers
+        // hats separately from the other axes.
+        let buttons = buttons
+            .iter()
+            .filter(|&&code| code >= BTN_JOYSTICK)
+            .chain(buttons.iter().filter(|&&code| code < BTN_JOYSTICK))
+            .copied()
+            .collect::<Vec<_>>();
+        let axes = abs_axes
+            .iter()
+            .copied()
+            .filter(|&axis| hat_of_axis(axis).is_none())
+            .collect::<Vec<_>>();
+        let mut hats = abs_axes
+            .iter()
+            .filter_map(|&axis| hat_of_axis(axis))
+            .collect::<Vec<_>>();
+        hats.dedup();
+
+        let missing = |entry: &Entry| SdlMappingError::MissingInput {
+            target: entry.target.clone(),
+            input: entry.input.clone(),
+        };
+        let mut layout = GamepadLayout::default();
+        for (target, input, entry) in &self.axes {
+            let source = match *input {
+                SdlInput::Button(index) => {
+                    AxisSource::Key(*buttons.get(index).ok_or_else(|| missing(entry))?)
+                }
+                SdlInput::Axis {
+                    index,
+                    half: None,
+                    invert,
+                } => AxisSource::Abs {
+                    axis: *axes.get(index).ok_or_else(|| missing(entry))?,
+                    invert,
+                },
+                SdlInput::Axis {
+                    index,
+                    half: Some(positive),
+                    invert: false,
+                } => AxisSource::AbsHalf {
+                    axis: *axes.get(index).ok_or_else(|| missing(entry))?,
+                    positive,
+                },
+                SdlInput::Axis { .. } | SdlInput::Hat { .. } => {
+                    return Err(SdlMappingError::InvalidInput {
+                        target: entry.target.clone(),
+                        input: entry.input.clone(),
+                    });
+                }
+            };
+            layout.map_axis(*target, source);
+        }
+        for (target, input, entry) in &self.buttons {
+            let source = match *input {
+                SdlInput::Button(index) => {
+                    ButtonSource::Key(*buttons.get(index).ok_or_else(|| missing(entry))?)
+                }
+                SdlInput::Axis {
+                    index,
+                    half,
+                    invert,
+                } => ButtonSource::Abs {
+                    axis: *axes.get(index).ok_or_else(|| missing(entry))?,
+                    positive: half.unwrap_or(true) != invert,
+                },
+                SdlInput::Hat { index, direction } => ButtonSource::Hat {
+                    hat: *hats.get(index).ok_or_else(|| missing(entry))?,
+                    direction,
+                },
+            };
+            layout.map_button(*target, source);
+        }
+        Ok(layout)
+    }
+}
+
+fn sdl_axis(target: &str) -> Option<CanonicalAxis> {
+    Some(match target {
+        "leftx" => CanonicalAxis::LeftStickX,
+        "lefty" => CanonicalAxis::LeftStickY,
+        "rightx" => CanonicalAxis::RightStickX,
+        "righty" => CanonicalAxis::RightStickY,
+        "lefttrigger" => CanonicalAxis::LeftTrigger,
+        "righttrigger" => CanonicalAxis::RightTrigger,
+        _ => return None,
+    })
+}
+
+fn sdl_button(target: &str) -> Option<CanonicalButton> {
+    Some(match target {
+        "a" => CanonicalButton::A,
+        "b" => CanonicalButton::B,
+        "x" => CanonicalButton::X,
+        "y" => CanonicalButton::Y,
+        "leftshoulder" => CanonicalButton::LeftShoulder,
+        "rightshoulder" => CanonicalButton::RightShoulder,
+        "back" => CanonicalButton::Back,
+        "start" => CanonicalButton::Start,
+        "guide" => CanonicalButton::Guide,
+        "leftstick" => CanonicalButton::LeftStick,
+        "rightstick" => CanonicalButton::RightStick,
+        "dpup" => CanonicalButton::DPadUp,
+        "dpdown" => CanonicalButton::DPadDown,
+        "dpleft" => CanonicalButton::DPadLeft,
+        "dpright" => CanonicalButton::DPadRight,
+        _ => return None,
+    })
+}
+
+/// SDL's joystick GUID: the bus, vendor, product and version as little-endian 16-bit words,
+/// or the start of the name when the device has no vendor and product id. The name CRC
+/// newer SDL versions store in the second word is left as zero, matching most
+/// `gamecontrollerdb.txt` entries.
+fn sdl_guid(id: InputId, name: &str) -> String {
+    let mut guid = [0u8; 16];
+    guid[0..2].copy_from_slice(&id.bustype.to_le_bytes());
+    if id.vendor != 0 && id.product != 0 {
+        guid[4..6].copy_from_slice(&id.vendor.to_le_bytes());
+        guid[8..10].copy_from_slice(&id.product.to_le_bytes());
+        guid[12..14].copy_from_slice(&id.version.to_le_bytes());
+    } else {
+        // Leaves room for the terminating NUL SDL copies along with the name.
+        let name = &name.as_bytes()[..name.len().min(11)];
+        guid[4..4 + name.len()].copy_from_slice(name);
+    }
+    guid.iter()
+        .fold(String::with_capacity(32), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+impl Joystick {
+    /// The GUID SDL identifies this device by, for finding its line in `gamecontrollerdb.txt`.
+    pub fn sdl_guid(&self) -> String {
+        sdl_guid(self.input_id(), &self.name().unwrap_or_default())
+    }
+
+    /// Resolves an SDL mapping against this device's inputs, without applying it.
+    pub fn sdl_layout(&self, mapping: &SdlMapping) -> Result<GamepadLayout, SdlMappingError> {
+        mapping.layout(
+            &self.buttons.keys().copied().collect::<Vec<_>>(),
+            &self.abs_axis,
+        )
+    }
+
+    /// Makes [`Joystick::gamepad`] use the layout described by an SDL `gamecontrollerdb.txt`
+    /// line instead of guessing one. The mapping's GUID isn't checked against
+    /// [`Joystick::sdl_guid`], so a mapping for a similar device can be reused.
+    pub fn apply_sdl_mapping(&mut self, mapping: &str) -> Result<(), SdlMappingError> {
+        let layout = self.sdl_layout(&mapping.parse()?)?;
+        self.gamepad_layout = Some(layout);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XBOX_360: &str = "030000005e0400008e02000014010000,Xbox 360 Controller,a:b0,b:b1,\
+        back:b6,dpdown:h0.4,dpleft:h0.8,dpright:h0.2,dpup:h0.1,guide:b8,leftshoulder:b4,\
+        leftstick:b9,lefttrigger:a2,leftx:a0,lefty:a1,rightshoulder:b5,rightstick:b10,\
+        righttrigger:a5,rightx:a3,righty:a4,start:b7,x:b2,y:b3,platform:Linux,";
+
+    #[test]
+    fn test_sdl_guid() {
+        let id = InputId {
+            bustype: 0x03,
+            vendor: 0x045e,
+            product: 0x028e,
+            version: 0x0114,
+        };
+        assert_eq!(sdl_guid(id, "Xbox 360"), "030000005e0400008e02000014010000");
+        let id = InputId {
+            bustype: 0x05,
+            vendor: 0,
+            product: 0,
+            version: 1,
+        };
+        assert_eq!(
+            sdl_guid(id, "Wireless Gamepad"),
+            "05000000576972656c65737320476100"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "xbox,Xbox".parse::<SdlMapping>(),
+            Err(SdlMappingError::InvalidGuid("xbox".to_owned()))
+        );
+        assert_eq!(
+            "030000005e0400008e02000014010000".parse::<SdlMapping>(),
+            Err(SdlMappingError::MissingName)
+        );
+        assert_eq!(
+            "030000005e0400008e02000014010000,Xbox,a".parse::<SdlMapping>(),
+            Err(SdlMappingError::MalformedEntry("a".to_owned()))
+        );
+        assert_eq!(
+            "030000005e0400008e02000014010000,Xbox,a:h0.3".parse::<SdlMapping>(),
+            Err(SdlMappingError::InvalidInput {
+                target: "a".to_owned(),
+                input: "h0.3".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let mapping = XBOX_360.parse::<SdlMapping>().expect("Mapping is valid");
+        assert_eq!(mapping.name, "Xbox 360 Controller");
+        let buttons = [
+            EV_KEY::BTN_SOUTH,
+            EV_KEY::BTN_EAST,
+            EV_KEY::BTN_NORTH,
+            EV_KEY::BTN_WEST,
+            EV_KEY::BTN_TL,
+            EV_KEY::BTN_TR,
+            EV_KEY::BTN_SELECT,
+            EV_KEY::BTN_START,
+            EV_KEY::BTN_MODE,
+            EV_KEY::BTN_THUMBL,
+            EV_KEY::BTN_THUMBR,
+        ]
+        .map(|key| key as u32);
+        let axes = [
+            EV_ABS::ABS_X,
+            EV_ABS::ABS_Y,
+            EV_ABS::ABS_Z,
+            EV_ABS::ABS_RX,
+            EV_ABS::ABS_RY,
+            EV_ABS::ABS_RZ,
+            EV_ABS::ABS_HAT0X,
+            EV_ABS::ABS_HAT0Y,
+        ];
+        let layout = mapping.layout(&buttons, &axes).expect("Inputs exist");
+        assert_eq!(
+            layout.button(CanonicalButton::X),
+            Some(ButtonSource::Key(EV_KEY::BTN_NORTH as u32))
+        );
+        assert_eq!(
+            layout.axis(CanonicalAxis::RightStickX),
+            Some(AxisSource::Abs {
+                axis: EV_ABS::ABS_RX,
+                invert: false
+            })
+        );
+        assert_eq!(
+            layout.button(CanonicalButton::DPadLeft),
+            Some(ButtonSource::Hat {
+                hat: 0,
+                direction: HatDirection::Left
+            })
+        );
+
+        let err = mapping
+            .layout(&buttons[..10], &axes)
+            .expect_err("b10 is missing");
+        assert_eq!(
+            err,
+            SdlMappingError::MissingInput {
+                target: "rightstick".to_owned(),
+                input: "b10".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn test_half_axes() {
+        let mapping = "030000005e0400008e02000014010000,Pad,lefttrigger:+a2,righttrigger:-a2,\
+            dpup:-a1,"
+            .parse::<SdlMapping>()
+            .expect("Mapping is valid");
+        let layout = mapping
+            .layout(&[], &[EV_ABS::ABS_X, EV_ABS::ABS_Y, EV_ABS::ABS_Z])
+            .expect("Inputs exist");
+        assert_eq!(
+            layout.axis(CanonicalAxis::RightTrigger),
+            Some(AxisSource::AbsHalf {
+                axis: EV_ABS::ABS_Z,
+                positive: false
+            })
+        );
+        assert_eq!(
+            layout.button(CanonicalButton::DPadUp),
+            Some(ButtonSource::Abs {
+                axis: EV_ABS::ABS_Y,
+                positive: false
+            })
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::{io, os::fd::AsFd};
+
+use evdev_rs::InputEvent;
+use nix::{
+    errno::Errno,
+    poll::{PollFd, PollFlags, PollTimeout, poll},
+};
+
+use super::{
+    Joystick,
+    error::{JoystickError, JoystickRemoved},
+    events::read_event,
+};
+
+/// Several joysticks read together through a single `poll(2)` loop.
+///
+/// Each joystick keeps the index it was added with, even after others are removed.
+#[derive(Debug, Default)]
+pub struct JoystickSet {
+    // Removed joysticks leave an empty slot behind, so indices stay stable.
+    joysticks: Vec<Option<Joystick>>,
+    // Where `next_event` starts looking, so one busy joystick can't starve the others.
+    next: usize,
+}
+
+impl JoystickSet {
+    pub fn new(joysticks: Vec<Joystick>) -> Self {
+        JoystickSet {
+            joysticks: joysticks.into_iter().map(Some).collect(),
+            next: 0,
+        }
+    }
+
+    /// Adds a joystick, returning the index its events will be reported with.
+    pub fn push(&mut self, joystick: Joystick) -> usize {
+        self.joysticks.push(Some(joystick));
+        self.joysticks.len() - 1
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Joystick> {
+        self.joysticks.get(index)?.as_ref()
+    }
+
+    /// Takes a joystick out of the set. Its index isn't reused.
+    pub fn remove(&mut self, index: usize) -> Option<Joystick> {
+        self.joysticks.get_mut(index)?.take()
+    }
+
+    /// The number of joysticks in the set, not counting removed ones.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Joystick> {
+        self.joysticks.iter().flatten()
+    }
+
+    /// Waits for the next event from any joystick, returning it with the joystick's index.
+    ///
+    /// A joystick that fails (e.g. because it was unplugged, see [`JoystickError`]) is removed
+    /// from the set and its error returned, wrapping a [`JoystickRemoved`] which
+    /// [`removed_joystick`](super::removed_joystick) extracts the index from. The other
+    /// joysticks keep being read on the next call. Fails with [`io::ErrorKind::NotFound`] once
+    /// the set is empty.
+    pub fn next_event(&mut self) -> io::Result<(usize, InputEvent)> {
+        loop {
+            let alive = self
+                .joysticks
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>();
+            if !alive.contains(&true) {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "no joysticks left in the set",
+                ));
+            }
+            match read_available(&self.joysticks, &alive, &mut self.next) {
+                Some((index, Ok(event))) => return Ok((index, event)),
+                Some((index, Err(source))) => {
+                    self.joysticks[index] = None;
+                    let source = match JoystickError::from(source) {
+                        JoystickError::Disconnected(source) => {
+                            log::info!(
+                                "joystick {index} was disconnected, removing it from the set"
+                            );
+                            source
+                        }
+                        JoystickError::Io(source) => {
+                            log::warn!(
+                                "joystick {index} failed, removing it from the set: {source}"
+                            );
+                            source
+                        }
+                    };
+                    return Err(io::Error::new(
+                        source.kind(),
+                        JoystickRemoved { index, source },
+                    ));
+                }
+                None => wait_readable(&self.joysticks, &alive)?,
+            }
+        }
+    }
+
+    /// Iterates over the events of every joystick, tagged with the joystick's index.
+    ///
+    /// When a joystick fails (e.g. it was unplugged) its error is yielded once and the
+    /// remaining joysticks keep being read. The iterator ends once every joystick has failed.
+    pub fn events(&self) -> JoystickSetEvents<'_> {
+        JoystickSetEvents {
+            joysticks: &self.joysticks,
+            alive: self.joysticks.iter().map(Option::is_some).collect(),
+            next: 0,
+        }
+    }
+}
+
+impl FromIterator<Joystick> for JoystickSet {
+    fn from_iter<T: IntoIterator<Item = Joystick>>(iter: T) -> Self {
+        JoystickSet::new(iter.into_iter().collect())
+    }
+}
+
+pub struct JoystickSetEvents<'a> {
+    joysticks: &'a [Option<Joystick>],
+    alive: Vec<bool>,
+    next: usize,
+}
+
+impl Iterator for JoystickSetEvents<'_> {
+    type Item = (usize, io::Result<InputEvent>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if !self.alive.contains(&true) {
+                return None;
+            }
+            match read_available(self.joysticks, &self.alive, &mut self.next) {
+                Some((index, Err(e))) => {
+                    self.alive[index] = false;
+                    return Some((index, Err(e)));
+                }
+                Some(item) => return Some(item),
+                None => (),
+            }
+            if let Err(e) = wait_readable(self.joysticks, &self.alive) {
+                // poll(2) itself failing leaves nothing to wait on, so report it and stop.
+                let index = self.alive.iter().position(|&alive| alive).unwrap_or(0);
+                self.alive.fill(false);
+                return Some((index, Err(e)));
+            }
+        }
+    }
+}
+
+/// Reads an event from the first live joystick that has one, starting at `next` and moving
+/// it past the joystick that produced the event.
+fn read_available(
+    joysticks: &[Option<Joystick>],
+    alive: &[bool],
+    next: &mut usize,
+) -> Option<(usize, io::Result<InputEvent>)> {
+    let count = joysticks.len();
+    for index in (0..count).map(|offset| (*next + offset) % count) {
+        let Some(joystick) = joysticks[index].as_ref().filter(|_| alive[index]) else {
+            continue;
+        };
+        match read_event(&joystick.device) {
+            Ok(Some(event)) => {
+                *next = index + 1;
+                return Some((index, Ok(event)));
+            }
+            Ok(None) => (),
+            Err(e) => return Some((index, Err(e))),
+        }
+    }
+    None
+}
+
+/// Blocks until one of the live joysticks is readable.
+fn wait_readable(joysticks: &[Option<Joystick>], alive: &[bool]) -> io::Result<()> {
+    let mut fds = joysticks
+        .iter()
+        .zip(alive)
+        .filter_map(|(joystick, &alive)| joystick.as_ref().filter(|_| alive))
+        .map(|joystick| PollFd::new(joystick.device.file().as_fd(), PollFlags::POLLIN))
+        .collect::<Vec<_>>();
+    match poll(&mut fds, PollTimeout::NONE) {
+        Ok(_) | Err(Errno::EINTR) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_disconnected, removed_joystick};
+
+    #[test]
+    fn test_empty_set() {
+        let set = JoystickSet::default();
+        assert!(set.is_empty());
+        assert!(set.events().next().is_none());
+    }
+
+    #[test]
+    fn test_two_joysticks() {
+        use evdev_rs::{
+            TimeVal,
+            enums::{EV_ABS, EV_SYN, EventCode},
+        };
+
+        let (Some((first, joystick0)), Some((second, joystick1))) = (
+            crate::uinput::test_joystick(),
+            crate::uinput::test_joystick(),
+        ) else {
+            return;
+        };
+        let x = EventCode::EV_ABS(EV_ABS::ABS_X);
+        for (device, value) in [(&first, 100), (&second, -100)] {
+            device
+                .emit(&[InputEvent::new(&TimeVal::new(0, 0), &x, value)])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+        }
+        let mut set = JoystickSet::new(vec![joystick0, joystick1]);
+        let events = (0..4)
+            .map(|_| {
+                let (index, event) = set.next_event().expect("Event is read");
+                (index, event.event_code, event.value)
+            })
+            .collect::<Vec<_>>();
+        let syn = EventCode::EV_SYN(EV_SYN::SYN_REPORT);
+        // Read in turns, so neither joystick starves the other.
+        assert_eq!(
+            events,
+            [(0, x, 100), (1, x, -100), (0, syn, 0), (1, syn, 0)]
+        );
+
+        // Unplugging one removes it, and the other keeps being read.
+        drop(first);
+        let err = set.next_event().expect_err("Disconnect is reported");
+        assert_eq!(removed_joystick(&err), Some(0));
+        assert!(is_disconnected(&err));
+        assert_eq!(set.len(), 1);
+        second
+            .emit(&[InputEvent::new(&TimeVal::new(0, 0), &x, 50)])
+            .expect("Event can be emitted");
+        let (index, event) = set.next_event().expect("Event is read");
+        assert_eq!((index, event.event_code, event.value), (1, x, 50));
+    }
+
+    #[test]
+    fn test_empty_set_next_event() {
+        let mut set = JoystickSet::default();
+        let err = set.next_event().expect_err("Empty set has no events");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
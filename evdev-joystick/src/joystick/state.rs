@@ -0,0 +1,194 @@
+use std::{collections::BTreeMap, io};
+
+use evdev_rs::enums::EV_ABS;
+
+use super::{HatDirection, Joystick, JoystickEvent};
+
+/// The raw and normalized value of an axis in a [`JoystickState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AxisState {
+    pub value: i32,
+    pub normalized: i16,
+}
+
+/// The current value of every axis and button of a joystick, for polling-style game loops.
+/// Seeded by [`Joystick::initial_state`] and brought up to date by [`Joystick::poll_state`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JoystickState {
+    axes: BTreeMap<EV_ABS, AxisState>,
+    buttons: BTreeMap<u32, bool>,
+    hats: BTreeMap<u8, HatDirection>,
+}
+
+impl JoystickState {
+    pub fn axis(&self, axis: EV_ABS) -> Option<AxisState> {
+        self.axes.get(&axis).copied()
+    }
+
+    /// Whether the button with the given index is held, see [`Joystick::get_button_index`].
+    pub fn button(&self, index: u32) -> Option<bool> {
+        self.buttons.get(&index).copied()
+    }
+
+    pub fn hat(&self, hat: u8) -> Option<HatDirection> {
+        self.hats.get(&hat).copied()
+    }
+
+    pub fn axes(&self) -> impl Iterator<Item = (EV_ABS, AxisState)> {
+        self.axes.iter().map(|(&axis, &state)| (axis, state))
+    }
+
+    pub fn buttons(&self) -> impl Iterator<Item = (u32, bool)> {
+        self.buttons
+            .iter()
+            .map(|(&index, &pressed)| (index, pressed))
+    }
+
+    /// Applies an event to the state, returning whether it changed anything.
+    pub fn apply(&mut self, event: &JoystickEvent) -> bool {
+        match *event {
+            JoystickEvent::AxisMoved {
+                axis,
+                value,
+                normalized,
+                ..
+            } => {
+                let state = AxisState { value, normalized };
+                self.axes.insert(axis, state) != Some(state)
+            }
+            JoystickEvent::ButtonChanged { index, pressed, .. } => {
+                self.buttons.insert(index, pressed) != Some(pressed)
+            }
+            JoystickEvent::HatMoved { hat, direction, .. } => {
+                self.hats.insert(hat, direction) != Some(direction)
+            }
+            JoystickEvent::Sync { .. } | JoystickEvent::Other(_) => false,
+        }
+    }
+}
+
+impl Joystick {
+    /// The current state of every axis, button and hat, with buttons read from `EVIOCGKEY`.
+    /// `BTN_DPAD_*` buttons are only tracked as hat 0, as they are reported as
+    /// [`JoystickEvent::HatMoved`].
+    pub fn initial_state(&self) -> io::Result<JoystickState> {
+        let axes = self
+            .all_abs_info()
+            .into_iter()
+            .map(|(axis, abs_info)| {
+                let state = AxisState {
+                    value: abs_info.value,
+                    normalized: abs_info.normalized_value(),
+                };
+                (axis, state)
+            })
+            .collect();
+        let mut buttons = self.button_states()?;
+        buttons.retain(|&index, _| !self.is_dpad_index(index));
+        let hats = (0..4)
+            .filter_map(|hat| Some((hat, self.hat_state(hat).ok()?)))
+            .collect();
+        Ok(JoystickState {
+            axes,
+            buttons,
+            hats,
+        })
+    }
+
+    /// Drains every pending event into `state` without waiting for new ones. If the kernel
+    /// dropped events (`SYN_DROPPED`), `state` is re-read with [`Joystick::initial_state`] once
+    /// the queue is empty, since the changes that were lost can't be applied.
+    pub fn poll_state(&self, state: &mut JoystickState) -> io::Result<()> {
+        let mut events = self.events_nonblocking();
+        for event in events.by_ref() {
+            state.apply(&self.classify_event(event?));
+        }
+        if events.take_dropped() {
+            *state = self.initial_state()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev_rs::{
+        InputEvent, TimeVal,
+        enums::{EV_KEY, EventCode},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let time = TimeVal::new(0, 0);
+        let mut state = JoystickState::default();
+        let moved = JoystickEvent::AxisMoved {
+            time,
+            axis: EV_ABS::ABS_X,
+            value: 200,
+            normalized: 100,
+        };
+        assert!(state.apply(&moved));
+        assert!(!state.apply(&moved));
+        assert_eq!(
+            state.axis(EV_ABS::ABS_X),
+            Some(AxisState {
+                value: 200,
+                normalized: 100
+            })
+        );
+        assert!(state.apply(&JoystickEvent::ButtonChanged {
+            time,
+            index: 3,
+            pressed: true,
+        }));
+        assert_eq!(state.button(3), Some(true));
+        assert_eq!(state.button(4), None);
+        assert!(!state.apply(&JoystickEvent::Sync { time }));
+    }
+
+    #[test]
+    fn test_poll_state_resync() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let time = TimeVal::new(0, 0);
+        let thumb = EventCode::EV_KEY(EV_KEY::BTN_THUMB);
+        let index = joystick
+            .get_button_index(&thumb)
+            .expect("Harness has BTN_THUMB");
+        let mut state = joystick.initial_state().expect("State can be read");
+        device
+            .emit(&[InputEvent::new(&time, &thumb, 1)])
+            .expect("Event can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        joystick
+            .poll_state(&mut state)
+            .expect("State can be polled");
+        assert_eq!(state.button(index), Some(true));
+
+        // The release is followed by far more reports than the kernel buffers for a reader,
+        // so it is dropped and only the resync can tell that the button is up.
+        device
+            .emit(&[InputEvent::new(&time, &thumb, 0)])
+            .expect("Event can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        for i in 0..1000 {
+            let value = if i % 2 == 0 { -100 } else { 100 };
+            device
+                .emit(&[InputEvent::new(
+                    &time,
+                    &EventCode::EV_ABS(EV_ABS::ABS_X),
+                    value,
+                )])
+                .expect("Event can be emitted");
+            device.syn().expect("SYN_REPORT can be emitted");
+        }
+        joystick
+            .poll_state(&mut state)
+            .expect("State can be polled");
+        assert_eq!(state.button(index), Some(false));
+        assert_eq!(state.axis(EV_ABS::ABS_X).map(|axis| axis.value), Some(100));
+    }
+}
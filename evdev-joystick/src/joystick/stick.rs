@@ -0,0 +1,117 @@
+use std::io;
+
+use evdev_rs::enums::EV_ABS;
+
+use super::{Joystick, axis_name};
+
+/// The position of a stick as a vector of two normalized axes in `[-1.0, 1.0]`, with `y`
+/// pointing down as evdev reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StickVector {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl StickVector {
+    pub fn new(x: f32, y: f32) -> Self {
+        StickVector { x, y }
+    }
+
+    pub fn magnitude(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// The angle in radians from the positive x axis, in `[-PI, PI]`.
+    pub fn angle(self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    /// Snaps the whole vector to zero if its magnitude is below `deadzone`. Unlike a deadzone
+    /// on each axis, this leaves a round dead region rather than a square one.
+    pub fn with_radial_deadzone(self, deadzone: f32) -> Self {
+        if self.magnitude() < deadzone {
+            StickVector::default()
+        } else {
+            self
+        }
+    }
+
+    /// Scales the vector down to a magnitude of 1.0 if it is longer, as square gates reach
+    /// about 1.41 in the corners.
+    pub fn clamped(self) -> Self {
+        let magnitude = self.magnitude();
+        if magnitude > 1.0 {
+            StickVector::new(self.x / magnitude, self.y / magnitude)
+        } else {
+            self
+        }
+    }
+}
+
+impl From<StickVector> for (f32, f32) {
+    fn from(vector: StickVector) -> Self {
+        (vector.x, vector.y)
+    }
+}
+
+impl Joystick {
+    /// The current position of the stick made of axes `x` and `y`, with its magnitude clamped
+    /// to 1.0. The axes' own `flat` is ignored, apply a radial deadzone to the vector instead.
+    pub fn stick_vector(&self, x: EV_ABS, y: EV_ABS) -> io::Result<StickVector> {
+        let component = |axis| -> io::Result<f32> {
+            let abs_info = self.abs_info_for(axis).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("joystick has no axis {}", axis_name(axis)),
+                )
+            })?;
+            let value = abs_info.normalized_value_with_deadzone(0);
+            Ok((f32::from(value) / f32::from(i16::MAX)).max(-1.0))
+        };
+        Ok(StickVector::new(component(x)?, component(y)?).clamped())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use evdev_rs::{InputEvent, TimeVal, enums::EventCode};
+
+    use super::*;
+
+    #[test]
+    fn test_stick_vector() {
+        let vector = StickVector::new(0.6, 0.8);
+        assert!((vector.magnitude() - 1.0).abs() < 1e-6);
+        assert_eq!(StickVector::new(0.0, 1.0).angle(), FRAC_PI_2);
+        assert_eq!(StickVector::new(1.0, 1.0).clamped().magnitude(), 1.0);
+        assert_eq!(vector.clamped(), vector);
+        // The dead region is round, so a diagonal just outside a square one still snaps.
+        let diagonal = StickVector::new(0.1, 0.1);
+        assert_eq!(diagonal.with_radial_deadzone(0.15), StickVector::default());
+        assert_eq!(diagonal.with_radial_deadzone(0.1), diagonal);
+        assert_eq!(<(f32, f32)>::from(vector), (0.6, 0.8));
+    }
+
+    #[test]
+    fn test_stick_vector_virtual() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let err = joystick
+            .stick_vector(EV_ABS::ABS_X, EV_ABS::ABS_HAT0X)
+            .expect_err("Virtual joystick has no hat");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        // Pushed into the corner, which a square gate reports as a magnitude of about 1.41.
+        let events = [EV_ABS::ABS_X, EV_ABS::ABS_Y]
+            .map(|axis| InputEvent::new(&TimeVal::new(0, 0), &EventCode::EV_ABS(axis), 511));
+        device.emit(&events).expect("Events can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        joystick.drain_events().for_each(drop);
+        let vector = joystick
+            .stick_vector(EV_ABS::ABS_X, EV_ABS::ABS_Y)
+            .expect("Virtual joystick has a stick");
+        assert!((vector.magnitude() - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    io,
+    os::fd::{AsRawFd, RawFd},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use evdev_rs::{Device, InputEvent};
+use futures_core::Stream;
+use tokio::io::{Interest, unix::AsyncFd};
+
+use super::{Joystick, events::poll_next_event};
+
+/// Asynchronous stream of a joystick's events, driven by tokio's reactor.
+///
+/// The stream ends after yielding an error, e.g. [`DeviceDisconnected`](super::DeviceDisconnected)
+/// once the device is unplugged.
+pub struct EventStream<'a> {
+    device: &'a Device,
+    // Registered on first poll, since registering requires a running tokio runtime.
+    fd: Option<AsyncFd<RawFd>>,
+    done: bool,
+}
+
+impl Joystick {
+    /// Streams events as they arrive. Must be polled from within a tokio runtime; for other
+    /// runtimes use the `async-io` feature's `Joystick::async_io_event_stream`.
+    ///
+    /// Each wake-up drains every event that is already available before waiting on the fd
+    /// again, and a `SYN_DROPPED` is resynced the same way as [`Joystick::events`] does.
+    pub fn event_stream(&self) -> EventStream<'_> {
+        EventStream {
+            device: &self.device,
+            fd: None,
+            done: false,
+        }
+    }
+}
+
+fn poll_read_ready(
+    fd: &mut Option<AsyncFd<RawFd>>,
+    device: &Device,
+    cx: &mut Context<'_>,
+) -> Poll<io::Result<()>> {
+    let fd = match fd {
+        Some(fd) => fd,
+        None => fd.insert(AsyncFd::with_interest(
+            device.file().as_raw_fd(),
+            Interest::READABLE,
+        )?),
+    };
+    match fd.poll_read_ready(cx) {
+        Poll::Ready(Ok(mut guard)) => {
+            guard.clear_ready();
+            Poll::Ready(Ok(()))
+        }
+        Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+        Poll::Pending => Poll::Pending,
+    }
+}
+
+impl Stream for EventStream<'_> {
+    type Item = io::Result<InputEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let EventStream { device, fd, done } = self.get_mut();
+        poll_next_event(device, done, || poll_read_ready(fd, device, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, pin::pin};
+
+    use evdev_rs::{
+        TimeVal,
+        enums::{EV_KEY, EventCode},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_event_stream() {
+        let Some((device, joystick)) = crate::uinput::test_joystick() else {
+            return;
+        };
+        let mut stream = pin!(joystick.event_stream());
+        device
+            .emit(&[InputEvent::new(
+                &TimeVal::new(0, 0),
+                &EventCode::EV_KEY(EV_KEY::BTN_THUMB),
+                1,
+            )])
+            .expect("Event can be emitted");
+        device.syn().expect("SYN_REPORT can be emitted");
+        let event = poll_fn(|cx| stream.as_mut().poll_next(cx))
+            .await
+            .expect("Stream yields an event")
+            .expect("Event is read without error");
+        assert_eq!(event.event_code, EventCode::EV_KEY(EV_KEY::BTN_THUMB));
+        assert_eq!(event.value, 1);
+    }
+}
@@ -1,2 +1,14 @@
+//! Reading joysticks through evdev.
+//!
+//! Events can also be awaited. Pick the `tokio` feature (`Joystick::event_stream`) when
+//! running on tokio, or the `async-io` feature (`Joystick::async_io_event_stream`) for
+//! smol, async-std and other runtimes built on `async-io`.
+//!
+//! The `udev` feature adds `HotplugMonitor`, which reports joysticks being plugged in and
+//! unplugged. It links against the system's libudev.
+
 mod joystick;
+mod raw;
+mod uinput;
 pub use joystick::*;
+pub use uinput::*;
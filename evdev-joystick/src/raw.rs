@@ -0,0 +1,220 @@
+//! Raw evdev ioctls, for the parts of the interface libevdev doesn't wrap.
+
+use std::{ffi::CStr, os::fd::RawFd};
+
+use libc::{c_int, c_ulong};
+use nix::{
+    errno::Errno, ioctl_read, ioctl_read_buf, ioctl_write_int, ioctl_write_ptr, request_code_read,
+    request_code_write, sys::ioctl::ioctl_num_type,
+};
+
+// Every evdev ioctl the crate issues itself, in the order of uapi/linux/input.h. The string
+// and bitmask reads take a slice, whose length is encoded in the request like the kernel's
+// `len` argument. libevdev already reads the id and name on open, so `EVIOCGID` and
+// `EVIOCGNAME` are only issued by the tests, to check its copies against the kernel.
+ioctl_read!(evioc_get_version, b'E', 0x01, c_int);
+#[cfg(test)]
+ioctl_read!(evioc_get_id, b'E', 0x02, libc::input_id);
+#[cfg(test)]
+ioctl_read_buf!(evioc_get_name, b'E', 0x06, u8);
+ioctl_read_buf!(evioc_get_prop, b'E', 0x09, c_ulong);
+ioctl_read_buf!(evioc_get_key, b'E', 0x18, c_ulong);
+ioctl_read_buf!(evioc_get_led, b'E', 0x19, c_ulong);
+// Declared `_IOW` by the kernel, but it writes the id of a newly uploaded effect back
+// into the struct, so callers must pass a pointer derived from a mutable reference.
+ioctl_write_ptr!(evioc_send_ff, b'E', 0x80, libc::ff_effect);
+ioctl_write_int!(evioc_remove_ff, b'E', 0x81);
+
+/// libevdev's name for an event code, e.g. `BTN_SOUTH`, or `None` if it has none.
+pub fn event_code_name(event_type: u32, code: u32) -> Option<&'static str> {
+    // SAFETY: libevdev returns either null or a pointer into its static name tables.
+    let name = unsafe { evdev_sys::libevdev_event_code_get_name(event_type, code) };
+    if name.is_null() {
+        return None;
+    }
+    // SAFETY: checked for null above, and the tables hold NUL-terminated ASCII.
+    unsafe { CStr::from_ptr(name) }.to_str().ok()
+}
+
+/// The request code of `EVIOCGABS(axis)`.
+pub const fn evioc_get_abs_request(axis: u32) -> ioctl_num_type {
+    request_code_read!(b'E', 0x40 + axis, size_of::<libc::input_absinfo>()) as ioctl_num_type
+}
+
+/// The request code of `EVIOCSABS(axis)`.
+pub const fn evioc_set_abs_request(axis: u32) -> ioctl_num_type {
+    request_code_write!(b'E', 0xc0 + axis, size_of::<libc::input_absinfo>()) as ioctl_num_type
+}
+
+/// The request code of `EVIOCGBIT(event_type, len)`.
+pub const fn evioc_get_bit_request(event_type: u32, len: usize) -> ioctl_num_type {
+    request_code_read!(b'E', 0x20 + event_type, len) as ioctl_num_type
+}
+
+/// `EVIOCSABS(axis)`, whose request code depends on the axis so can't use the `ioctl_*!`
+/// macros. Writes the range, fuzz, flat and resolution of an axis.
+pub unsafe fn evioc_set_abs(
+    fd: RawFd,
+    axis: u32,
+    abs_info: *const libc::input_absinfo,
+) -> nix::Result<c_int> {
+    let request = evioc_set_abs_request(axis);
+    // SAFETY: the caller guarantees `abs_info` points to a valid input_absinfo.
+    Errno::result(unsafe { libc::ioctl(fd, request, abs_info) })
+}
+
+/// `EVIOCGABS(axis)`, the read counterpart of [`evioc_set_abs`]. Reads the current info of
+/// an axis straight from the kernel.
+pub unsafe fn evioc_get_abs(
+    fd: RawFd,
+    axis: u32,
+    abs_info: *mut libc::input_absinfo,
+) -> nix::Result<c_int> {
+    let request = evioc_get_abs_request(axis);
+    // SAFETY: the caller guarantees `abs_info` points to writable input_absinfo.
+    Errno::result(unsafe { libc::ioctl(fd, request, abs_info) })
+}
+
+/// `EVIOCGBIT(event_type, len)`: fills `bits` with the codes of `event_type` the device
+/// supports. The request code depends on the event type, like [`evioc_set_abs`].
+pub unsafe fn evioc_get_bit(
+    fd: RawFd,
+    event_type: u32,
+    bits: &mut [c_ulong],
+) -> nix::Result<c_int> {
+    let request = evioc_get_bit_request(event_type, size_of_val(bits));
+    // SAFETY: the request carries the buffer's length, so the kernel stays within it.
+    Errno::result(unsafe { libc::ioctl(fd, request, bits.as_mut_ptr()) })
+}
+
+/// Reads a string with one of the `EVIOCG*` string ioctls. The kernel fails with `ENOENT`
+/// if the device has no such string, which is returned as `None` like an empty one.
+#[cfg(test)]
+pub fn read_string(
+    fd: RawFd,
+    ioctl: unsafe fn(RawFd, &mut [u8]) -> nix::Result<c_int>,
+) -> std::io::Result<Option<String>> {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer's length is passed along with it.
+    match unsafe { ioctl(fd, &mut buf) } {
+        Ok(_) => (),
+        Err(Errno::ENOENT) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(Some(String::from_utf8_lossy(&buf[..len]).into_owned()).filter(|s| !s.is_empty()))
+}
+
+const LONG_BITS: usize = c_ulong::BITS as usize;
+
+/// A kernel bitmask able to hold bits `0..=max`, as filled in by the `EVIOCG*` bit ioctls.
+/// The kernel works in longs, so the mask must too to get the bit order right on
+/// big-endian machines.
+pub fn bitmask(max: usize) -> Vec<c_ulong> {
+    vec![0; max / LONG_BITS + 1]
+}
+
+/// Whether `bit` is set in a kernel bitmask.
+pub fn test_bit(bits: &[c_ulong], bit: usize) -> bool {
+    bits.get(bit / LONG_BITS)
+        .is_some_and(|long| long & (1 << (bit % LONG_BITS)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From uapi/linux/input.h: `EVIOCGABS(abs)` is `_IOR('E', 0x40 + (abs), struct
+    // input_absinfo)`, `EVIOCSABS(abs)` is `_IOW('E', 0xc0 + (abs), struct input_absinfo)` and
+    // `EVIOCGBIT(ev, len)` is `_IOC(_IOC_READ, 'E', 0x20 + (ev), len)`, with the struct 24
+    // bytes everywhere. mips, powerpc and sparc use a 3 bit direction field with different
+    // read and write bits than asm-generic/ioctl.h, so their codes differ.
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "sparc",
+            target_arch = "sparc64"
+        ))] {
+            const EVIOCGABS_0: u32 = 0x4018_4540;
+            const EVIOCSABS_0: u32 = 0x8018_45c0;
+            const EVIOCGBIT_ABS_8: u32 = 0x4008_4523;
+            const EVIOCGVERSION: u32 = 0x4004_4501;
+            const EVIOCGID: u32 = 0x4008_4502;
+            const EVIOCGNAME_256: u32 = 0x4100_4506;
+            const EVIOCSCLOCKID: u32 = 0x8004_45a0;
+        } else {
+            const EVIOCGABS_0: u32 = 0x8018_4540;
+            const EVIOCSABS_0: u32 = 0x4018_45c0;
+            const EVIOCGBIT_ABS_8: u32 = 0x8008_4523;
+            const EVIOCGVERSION: u32 = 0x8004_4501;
+            const EVIOCGID: u32 = 0x8008_4502;
+            const EVIOCGNAME_256: u32 = 0x8100_4506;
+            const EVIOCSCLOCKID: u32 = 0x4004_45a0;
+        }
+    }
+
+    // Checked at compile time too, so a wrong encoding fails the test build on a port.
+    const _: () = assert!(evioc_get_abs_request(0) as u32 == EVIOCGABS_0);
+    const _: () = assert!(evioc_set_abs_request(0) as u32 == EVIOCSABS_0);
+
+    #[test]
+    fn test_request_codes() {
+        assert_eq!(evioc_get_abs_request(0) as u32, EVIOCGABS_0);
+        assert_eq!(evioc_set_abs_request(0) as u32, EVIOCSABS_0);
+        // The axis only adds to the number field.
+        assert_eq!(evioc_get_abs_request(0x3f) as u32, EVIOCGABS_0 + 0x3f);
+        assert_eq!(evioc_set_abs_request(1) as u32, EVIOCSABS_0 + 1);
+        let abs = evdev_rs::enums::EventType::EV_ABS as u32;
+        assert_eq!(evioc_get_bit_request(abs, 8) as u32, EVIOCGBIT_ABS_8);
+    }
+
+    #[test]
+    fn test_fixed_request_codes() {
+        // The same parameters the `ioctl_*!` declarations above are given.
+        let codes = [
+            (
+                request_code_read!(b'E', 0x01, size_of::<c_int>()),
+                EVIOCGVERSION,
+            ),
+            (
+                request_code_read!(b'E', 0x02, size_of::<libc::input_id>()),
+                EVIOCGID,
+            ),
+            (request_code_read!(b'E', 0x06, 256), EVIOCGNAME_256),
+            (
+                request_code_write!(b'E', 0xa0, size_of::<c_int>()),
+                EVIOCSCLOCKID,
+            ),
+        ];
+        for (code, expected) in codes {
+            assert_eq!(code as u32, expected, "{code:#x} != {expected:#x}");
+        }
+    }
+
+    #[test]
+    fn test_event_code_name() {
+        let key = evdev_rs::enums::EventType::EV_KEY as u32;
+        assert_eq!(event_code_name(key, 0x130), Some("BTN_SOUTH"));
+        assert_eq!(event_code_name(key, 0x120), Some("BTN_TRIGGER"));
+        assert_eq!(event_code_name(key, 0x2fe), None);
+    }
+
+    #[test]
+    fn test_test_bit() {
+        let mut bits = bitmask(LONG_BITS);
+        assert_eq!(bits.len(), 2);
+        bits[0] = 0b101;
+        bits[1] = 1;
+        assert!(test_bit(&bits, 0));
+        assert!(!test_bit(&bits, 1));
+        assert!(test_bit(&bits, 2));
+        assert!(test_bit(&bits, LONG_BITS));
+        assert!(!test_bit(&bits, LONG_BITS + 1));
+        assert!(!test_bit(&bits, 2 * LONG_BITS));
+    }
+}
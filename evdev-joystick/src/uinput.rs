@@ -0,0 +1,287 @@
+use std::io::{self, Read};
+
+use evdev_rs::{
+    AbsInfo, DeviceWrapper, EnableCodeData, InputEvent, TimeVal, UInputDevice, UninitDevice,
+    enums::{BusType, EV_ABS, EV_KEY, EV_SYN, EventCode},
+};
+
+use crate::{EventTrace, joystick::record::replay_events};
+
+/// A joystick created through `/dev/uinput`, for emitting events as a virtual device.
+#[derive(Debug)]
+pub struct VirtualJoystick {
+    device: UInputDevice,
+}
+
+impl VirtualJoystick {
+    pub fn builder(name: impl Into<String>) -> VirtualJoystickBuilder {
+        VirtualJoystickBuilder {
+            name: name.into(),
+            vendor: 0,
+            product: 0,
+            buttons: Vec::new(),
+            abs_axis: Vec::new(),
+        }
+    }
+
+    /// The path of the created event node, e.g. `/dev/input/event42`.
+    pub fn devnode(&self) -> Option<&str> {
+        self.device.devnode()
+    }
+
+    /// Writes `events` to the device. The time of each event is ignored, the kernel stamps
+    /// them on arrival.
+    pub fn emit(&self, events: &[InputEvent]) -> io::Result<()> {
+        events
+            .iter()
+            .try_for_each(|event| self.device.write_event(event))
+    }
+
+    /// Emits a `SYN_REPORT`, marking the end of a batch of events.
+    pub fn syn(&self) -> io::Result<()> {
+        self.device.write_event(&InputEvent::new(
+            &TimeVal::new(0, 0),
+            &EventCode::EV_SYN(EV_SYN::SYN_REPORT),
+            0,
+        ))
+    }
+}
+
+/// Options for [`replay_to_uinput`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    /// How many times faster than recorded the trace is played back.
+    pub speed: f64,
+    /// Starts the trace over once it ends, until an error occurs. An empty trace still ends.
+    pub looping: bool,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        ReplayOptions {
+            speed: 1.0,
+            looping: false,
+        }
+    }
+}
+
+/// Emits the events of a trace recorded with [`Joystick::record`](crate::Joystick::record) on
+/// `joystick`, keeping their timing. Every event is written as recorded, including the
+/// `SYN_REPORT`s between frames, so readers see the same frames as the original device
+/// produced.
+pub fn replay_to_uinput(
+    reader: impl Read,
+    joystick: &VirtualJoystick,
+    options: ReplayOptions,
+) -> io::Result<()> {
+    let trace = EventTrace::new(reader)?;
+    if !options.looping {
+        return replay_events(trace, options.speed, |event| joystick.emit(&[event]));
+    }
+    // Looping needs the trace again after it ends, so keep it in memory.
+    let events = trace.collect::<io::Result<Vec<_>>>()?;
+    if events.is_empty() {
+        return Ok(());
+    }
+    loop {
+        replay_events(events.iter().copied().map(Ok), options.speed, |event| {
+            joystick.emit(&[event])
+        })?;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VirtualJoystickBuilder {
+    name: String,
+    vendor: u16,
+    product: u16,
+    buttons: Vec<EV_KEY>,
+    abs_axis: Vec<(EV_ABS, AbsInfo)>,
+}
+
+impl VirtualJoystickBuilder {
+    pub fn vendor(mut self, vendor: u16) -> Self {
+        self.vendor = vendor;
+        self
+    }
+
+    pub fn product(mut self, product: u16) -> Self {
+        self.product = product;
+        self
+    }
+
+    pub fn button(mut self, button: EV_KEY) -> Self {
+        self.buttons.push(button);
+        self
+    }
+
+    pub fn abs_axis(mut self, axis: EV_ABS, abs_info: AbsInfo) -> Self {
+        self.abs_axis.push((axis, abs_info));
+        self
+    }
+
+    pub fn build(self) -> io::Result<VirtualJoystick> {
+        let device = UninitDevice::new()
+            .ok_or_else(|| io::Error::other("libevdev could not allocate a device"))?;
+        device.set_name(&self.name);
+        device.set_bustype(BusType::BUS_VIRTUAL as u16);
+        device.set_vendor_id(self.vendor);
+        device.set_product_id(self.product);
+        for button in self.buttons {
+            device.enable_event_code(&EventCode::EV_KEY(button), None)?;
+        }
+        for (axis, abs_info) in self.abs_axis {
+            device.enable_event_code(
+                &EventCode::EV_ABS(axis),
+                Some(EnableCodeData::AbsInfo(abs_info)),
+            )?;
+        }
+        UInputDevice::create_from_device(&device).map(|device| VirtualJoystick { device })
+    }
+}
+
+/// Whether a [`VirtualJoystick`] can be created here. Tests needing one return early when it
+/// can't, e.g. in containers without `/dev/uinput`.
+#[cfg(test)]
+pub(crate) fn uinput_writable() -> bool {
+    let writable = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok();
+    if !writable {
+        log::warn!("/dev/uinput is not writable, skipping the test");
+    }
+    writable
+}
+
+/// A virtual joystick for tests, opened as a [`Joystick`](crate::Joystick): `ABS_X` and
+/// `ABS_Y` over `-512..=511` with a flat of 16, `ABS_RZ` over `0..=255`, and `BTN_TRIGGER`,
+/// `BTN_THUMB` and `BTN_THUMB2`. The device is removed when the [`VirtualJoystick`] is
+/// dropped. `None` if `/dev/uinput` isn't writable.
+#[cfg(test)]
+pub(crate) fn test_joystick() -> Option<(VirtualJoystick, crate::Joystick)> {
+    if !uinput_writable() {
+        return None;
+    }
+    let stick = AbsInfo {
+        value: 0,
+        minimum: -512,
+        maximum: 511,
+        fuzz: 0,
+        flat: 16,
+        resolution: 0,
+    };
+    let trigger = AbsInfo {
+        minimum: 0,
+        maximum: 255,
+        flat: 0,
+        ..stick
+    };
+    let virtual_joystick = VirtualJoystick::builder("evdev-joystick test harness")
+        .vendor(0x1234)
+        .product(0x5678)
+        .button(EV_KEY::BTN_TRIGGER)
+        .button(EV_KEY::BTN_THUMB)
+        .button(EV_KEY::BTN_THUMB2)
+        .abs_axis(EV_ABS::ABS_X, stick)
+        .abs_axis(EV_ABS::ABS_Y, stick)
+        .abs_axis(EV_ABS::ABS_RZ, trigger)
+        .build()
+        .expect("Virtual joystick can be created");
+    let joystick = crate::Joystick::new_from_path(
+        virtual_joystick
+            .devnode()
+            .expect("Virtual joystick has a device node"),
+    )
+    .expect("Virtual joystick can be opened");
+    Some((virtual_joystick, joystick))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventRecorder;
+
+    #[test]
+    fn test_virtual_joystick() {
+        let Some((virtual_joystick, joystick)) = test_joystick() else {
+            return;
+        };
+        assert_eq!(
+            (joystick.vendor_id(), joystick.product_id()),
+            (0x1234, 0x5678)
+        );
+        assert_eq!(joystick.buttons().count(), 3);
+        assert_eq!(
+            joystick.abs_axis().collect::<Vec<_>>(),
+            [EV_ABS::ABS_X, EV_ABS::ABS_Y, EV_ABS::ABS_RZ]
+        );
+        virtual_joystick
+            .emit(&[InputEvent::new(
+                &TimeVal::new(0, 0),
+                &EventCode::EV_ABS(EV_ABS::ABS_X),
+                511,
+            )])
+            .expect("Event can be emitted");
+        virtual_joystick.syn().expect("SYN_REPORT can be emitted");
+        let event = joystick
+            .events()
+            .next()
+            .expect("Emitted event is read")
+            .expect("Event is read without error");
+        assert_eq!(event.event_code, EventCode::EV_ABS(EV_ABS::ABS_X));
+        assert_eq!(event.value, 511);
+    }
+
+    #[test]
+    fn test_replay_to_uinput() {
+        let Some((virtual_joystick, joystick)) = test_joystick() else {
+            return;
+        };
+
+        let frames = [
+            (0, EventCode::EV_ABS(EV_ABS::ABS_X), 100),
+            (0, EventCode::EV_KEY(EV_KEY::BTN_TRIGGER), 1),
+            (0, EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
+            (50_000, EventCode::EV_ABS(EV_ABS::ABS_X), -100),
+            (50_000, EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
+        ];
+        let mut recorder = EventRecorder::new(Vec::new()).expect("Vec accepts the header");
+        for (usec, code, value) in &frames {
+            recorder
+                .write_event(&InputEvent::new(&TimeVal::new(1, *usec), code, *value))
+                .expect("Vec accepts events");
+        }
+        let options = ReplayOptions {
+            speed: 10.0,
+            ..Default::default()
+        };
+        replay_to_uinput(recorder.into_inner().as_slice(), &virtual_joystick, options)
+            .expect("Trace replays");
+
+        let replayed = joystick
+            .events_nonblocking()
+            .map(|event| event.map(|event| (event.event_code, event.value)))
+            .collect::<io::Result<Vec<_>>>()
+            .expect("Events are read");
+        let expected = frames
+            .iter()
+            .map(|&(_, code, value)| (code, value))
+            .collect::<Vec<_>>();
+        assert_eq!(replayed, expected);
+    }
+
+    #[test]
+    fn test_harness_buttons() {
+        let Some((_device, joystick)) = test_joystick() else {
+            return;
+        };
+        assert_eq!(joystick.num_buttons(), 3);
+        assert_eq!(
+            joystick.get_button_index(&EventCode::EV_KEY(EV_KEY::BTN_TRIGGER)),
+            Some(0)
+        );
+        assert_eq!(joystick.button_name(1), Some("BTN_THUMB"));
+        assert_eq!(joystick.button_code(2), Some(EV_KEY::BTN_THUMB2 as u32));
+    }
+}